@@ -0,0 +1,92 @@
+use crate::prelude::*;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use utils::prelude::*;
+
+static CONFIG_FILE: &str = "config.toml";
+
+// CLI-wide settings read once from `~/.config/mlx-client/config.toml`.
+// A missing file or missing fields fall back to the documented defaults,
+// so the config file is entirely optional.
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct ClientConfig {
+    // Fixed server URL, skipping the local/remote probing entirely. The
+    // MLX_SERVER_URL environment variable takes precedence over this.
+    pub server_url: Option<String>,
+
+    // Image registry ("host[/path]") used for deploy pushes. The
+    // MLX_IMAGE_REGISTRY environment variable takes precedence over this.
+    pub image_registry: Option<String>,
+
+    // Seconds an idle pooled connection stays warm before being dropped.
+    // Default: 90. Raising this helps high-frequency command patterns
+    // like `jobs --watch` and parallel deploys reuse connections.
+    pub pool_idle_timeout_secs: u64,
+
+    // Maximum idle connections kept alive per host. Default: 8.
+    pub pool_max_idle_per_host: usize,
+
+    // Seconds the startup update check waits for GitHub before giving up
+    // and letting the command proceed. Default: 2.
+    pub update_check_timeout_secs: u64,
+
+    // Redis connection string used by `serve run` and `xp logs`. The
+    // MLX_REDIS_URL environment variable takes precedence over this.
+    pub redis_url: Option<String>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            server_url: None,
+            image_registry: None,
+            pool_idle_timeout_secs: 90,
+            pool_max_idle_per_host: 8,
+            update_check_timeout_secs: 2,
+            redis_url: None,
+        }
+    }
+}
+
+// Resolves the Redis connection string: MLX_REDIS_URL wins, then the
+// config file. There is no sensible default to fall back to, so a missing
+// value fails here with instructions rather than deep inside
+// RedisManager::new.
+pub fn redis_url() -> RResult<String, AnyErr2> {
+    if let Ok(url) = std::env::var("MLX_REDIS_URL") {
+        if !url.is_empty() {
+            return Ok(url);
+        }
+    }
+
+    if let Some(url) = &CLIENT_CONFIG.redis_url {
+        return Ok(url.clone());
+    }
+
+    Err(Report::new(err2!(
+        "No Redis URL configured - set MLX_REDIS_URL or add redis_url to the config file"
+    )))
+}
+
+pub static CLIENT_CONFIG: Lazy<ClientConfig> = Lazy::new(load_config);
+
+fn load_config() -> ClientConfig {
+    let Some(mut path) = dirs_next::config_dir() else {
+        return ClientConfig::default();
+    };
+    path.push(crate::APP_NAME);
+    path.push(CONFIG_FILE);
+
+    match std::fs::read_to_string(&path) {
+        Ok(raw) => toml::from_str(&raw).unwrap_or_else(|e| {
+            warn!(
+                "Failed to parse {}: {} - falling back to defaults",
+                path.display(),
+                e
+            );
+            ClientConfig::default()
+        }),
+        Err(_) => ClientConfig::default(),
+    }
+}
@@ -0,0 +1,183 @@
+use crate::serve::{confirm, get_server_url, send_endpoint};
+use serde::Deserialize;
+use utils::endpoints::{Endpoint, Method};
+use utils::prelude::*;
+
+// One entry in the shared NFS tree as returned by /data/ls. Directories
+// carry their children inline; files leave the list empty.
+#[derive(Deserialize, Debug)]
+pub struct DataNode {
+    pub name: String,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub children: Vec<DataNode>,
+}
+
+// Renders the shared NFS filesystem as an indented tree with per-entry
+// sizes and the total at the bottom.
+#[tokio::main]
+pub async fn show_data_fs() -> RResult<(), AnyErr2> {
+    let endpoint = Endpoint::builder()
+        .base_url(&get_server_url().await?)
+        .endpoint("/data/ls")
+        .method(Method::GET)
+        .build()
+        .unwrap();
+
+    let response = send_endpoint(
+        endpoint,
+        "GET",
+        "/data/ls",
+        None,
+        "Failed to retrieve the data filesystem",
+    )
+    .await?;
+
+    let root: DataNode = serde_json::from_value(response)
+        .change_context(err2!("Malformed /data/ls response - expected a file tree"))?;
+
+    let total = print_tree(&root);
+    println!("Total: {}", format_size(total));
+
+    Ok(())
+}
+
+// Walks the tree with an explicit stack so arbitrarily deep directory
+// structures can't overflow the call stack. Returns the summed size of
+// every file visited.
+fn print_tree(root: &DataNode) -> u64 {
+    let mut total = 0;
+    let mut stack: Vec<(&DataNode, usize)> = vec![(root, 0)];
+
+    while let Some((node, depth)) = stack.pop() {
+        if node.children.is_empty() {
+            total += node.size;
+            println!(
+                "{}{} ({})",
+                "  ".repeat(depth),
+                node.name,
+                format_size(node.size)
+            );
+        } else {
+            println!("{}{}/", "  ".repeat(depth), node.name);
+            // Reverse so children print in their original order off the stack.
+            for child in node.children.iter().rev() {
+                stack.push((child, depth + 1));
+            }
+        }
+    }
+
+    total
+}
+
+// Deletes a folder from the shared NFS. This filesystem is shared by
+// everyone on the cluster, so we confirm with the path and its size
+// before sending anything; --yes skips the prompt for scripting.
+#[tokio::main]
+pub async fn remove_data_folder(path: &str, yes: bool) -> RResult<(), AnyErr2> {
+    let trimmed = path.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        return Err(Report::new(err2!(
+            "Refusing to remove the NFS root - pass a folder path"
+        )));
+    }
+
+    let size = fetch_path_size(trimmed).await?;
+
+    if !yes
+        && !confirm(&format!(
+            "Remove {} ({}) from the shared NFS? [y/N]: ",
+            trimmed,
+            format_size(size)
+        ))
+    {
+        info!("Aborted - {} left untouched", trimmed);
+        return Ok(());
+    }
+
+    let body = serde_json::json!({ "path": trimmed });
+    let endpoint = Endpoint::builder()
+        .base_url(&get_server_url().await?)
+        .endpoint("/data/rm")
+        .method(Method::POST)
+        .json_body(body.clone())
+        .build()
+        .unwrap();
+
+    send_endpoint(
+        endpoint,
+        "POST",
+        "/data/rm",
+        Some(&body),
+        "Failed to remove the data folder",
+    )
+    .await?;
+
+    info!("Removed {} from the shared NFS", trimmed);
+
+    Ok(())
+}
+
+// Looks the path up in the /data/ls tree so the confirmation prompt can
+// show how much data is about to go away.
+async fn fetch_path_size(path: &str) -> RResult<u64, AnyErr2> {
+    let endpoint = Endpoint::builder()
+        .base_url(&get_server_url().await?)
+        .endpoint("/data/ls")
+        .method(Method::GET)
+        .build()
+        .unwrap();
+
+    let response = send_endpoint(
+        endpoint,
+        "GET",
+        "/data/ls",
+        None,
+        "Failed to retrieve the data filesystem",
+    )
+    .await?;
+
+    let root: DataNode = serde_json::from_value(response)
+        .change_context(err2!("Malformed /data/ls response - expected a file tree"))?;
+
+    let mut node = &root;
+    for segment in path.split('/') {
+        node = node
+            .children
+            .iter()
+            .find(|child| child.name == segment)
+            .ok_or_else(|| err2!(format!("Path '{}' does not exist on the shared NFS", path)))?;
+    }
+
+    Ok(subtree_size(node))
+}
+
+fn subtree_size(root: &DataNode) -> u64 {
+    let mut total = 0;
+    let mut stack: Vec<&DataNode> = vec![root];
+
+    while let Some(node) = stack.pop() {
+        total += node.size;
+        stack.extend(node.children.iter());
+    }
+
+    total
+}
+
+fn format_size(bytes: u64) -> String {
+    const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+    const MIB: f64 = 1024.0 * 1024.0;
+    const KIB: f64 = 1024.0;
+
+    let bytes = bytes as f64;
+    if bytes >= GIB {
+        format!("{:.2} GiB", bytes / GIB)
+    } else if bytes >= MIB {
+        format!("{:.1} MiB", bytes / MIB)
+    } else if bytes >= KIB {
+        format!("{:.1} KiB", bytes / KIB)
+    } else {
+        format!("{} B", bytes)
+    }
+}
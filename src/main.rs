@@ -1,12 +1,15 @@
 use clap::{Parser, Subcommand};
 use std::{io::Write, path::Path, process::Command};
+mod config;
+mod data;
 mod prelude;
 mod serve;
 mod xp;
 pub use reqwest::Method;
 use serve::{
-    delete_service, deploy_service, jobs_service, list_services, log_service, run_tests,
-    scale_service, ScaleServiceConf, TomlConfig,
+    delete_versions, deploy_service, jobs_service, list_services, log_service, pull_schema_service,
+    run_tests, scale_service, status_service, DeployServiceConf, ScaleServiceConf, TomlConfig,
+    TzDisplay,
 };
 use tokio::runtime::Runtime;
 use tracing_subscriber::{filter::EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
@@ -15,7 +18,7 @@ use utils::{
     files::assert_files_exist,
     prelude::*,
 };
-use xp::stream_logs;
+use xp::{stream_logs, LogFormat};
 
 static APP_NAME: &str = "mlx-client";
 static TRAIN_REPO_URL: &str = "https://github.com/Wondera-AI/mlx.git";
@@ -29,14 +32,75 @@ static SERVICE_DOCKERFILE_PATH: &str = "Dockerfile";
 static RAY_ADDRESS: &str = "auto";
 // static SERVER_ADDRESS: &str = "http://3.132.162.86:30000";
 
+// Shown by --version: the crate version plus the installed commit hash,
+// so a bug report pins the exact build. A missing hash file reads as
+// "unknown" rather than erroring.
+static VERSION: once_cell::sync::Lazy<String> = once_cell::sync::Lazy::new(|| {
+    let hash = match read_current_commit_hash() {
+        Ok(hash) if !hash.is_empty() => hash,
+        _ => "unknown".to_string(),
+    };
+    format!("{} ({})", env!("CARGO_PKG_VERSION"), hash)
+});
+
 #[derive(Parser)]
 #[command(name = "MLX")]
+#[command(version = VERSION.as_str())]
 #[command(about = "Machine Learning Experiments", long_about = None)]
 struct Cli {
+    #[arg(
+        long,
+        global = true,
+        help = "Log every HTTP request and response (secrets redacted) for debugging"
+    )]
+    trace_http: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Never prompt for input; fail instead (for scripts)"
+    )]
+    no_interactive: bool,
+
+    #[arg(long, global = true, help = "Suppress informational banners")]
+    quiet: bool,
+
+    #[arg(
+        long,
+        global = true,
+        env = "MLX_NO_UPDATE_CHECK",
+        help = "Skip the startup update check"
+    )]
+    no_update: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Target this server URL, skipping env/config/probe resolution"
+    )]
+    server: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        env = "MLX_LOG_FORMAT",
+        help = "Format of the CLI's own log output",
+        default_value = "human"
+    )]
+    log_format: CliLogFormat,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+// Output format of the CLI's own tracing diagnostics.
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum CliLogFormat {
+    Human,
+    Json,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     #[command(about = "Control training experiments")]
@@ -59,6 +123,10 @@ enum Commands {
         #[command(subcommand)]
         action: ServeActions,
     },
+    #[command(about = "Check the local environment for common problems")]
+    Doctor,
+    #[command(about = "Update mlx-client to the latest version")]
+    Update,
 }
 
 #[derive(Subcommand)]
@@ -67,6 +135,17 @@ enum TrainActions {
     New {
         #[arg(help = "The name of the new training experiment")]
         name: String,
+        #[arg(
+            long = "ref",
+            help = "Template branch, tag or sha to pin the scaffold to",
+            default_value = "main"
+        )]
+        git_ref: String,
+        #[arg(
+            long,
+            help = "Run the template's mlx-postcreate.sh hook without asking"
+        )]
+        run_hooks: bool,
     },
     #[command(
         about = "Automatically generate the configuration yaml from the experiment definition"
@@ -96,6 +175,13 @@ enum XpActions {
         name: String,
         #[arg(help = "Run identifier of the experiment")]
         run: String,
+        #[arg(
+            long,
+            value_enum,
+            help = "Emit the stream as plain text or JSON lines",
+            default_value = "text"
+        )]
+        format: LogFormat,
     },
     #[command(about = "Live tensorboards of a particular experiment")]
     Board {
@@ -105,7 +191,15 @@ enum XpActions {
         run: String,
     },
     #[command(about = "Ray cluster monitor to view jobs, logs, and cluster-specific metrics")]
-    Ray,
+    Ray {
+        #[arg(
+            long,
+            env = "RAY_ADDRESS",
+            default_value = "auto",
+            help = "Ray cluster address; \"auto\" assumes a local Ray"
+        )]
+        ray_address: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -126,7 +220,12 @@ enum DataActions {
         ray_address: Option<String>,
     },
     #[command(about = "Remove a folder from the shared NFS")]
-    Rm,
+    Rm {
+        #[arg(help = "Path of the folder to remove, relative to the NFS root")]
+        path: String,
+        #[arg(long, help = "Skip the confirmation prompt")]
+        yes: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -135,6 +234,17 @@ enum ServeActions {
     New {
         #[arg(help = "Name of the service")]
         name: String,
+        #[arg(
+            long = "ref",
+            help = "Template branch, tag or sha to pin the scaffold to",
+            default_value = "main"
+        )]
+        git_ref: String,
+        #[arg(
+            long,
+            help = "Run the template's mlx-postcreate.sh hook without asking"
+        )]
+        run_hooks: bool,
     },
     #[command(about = "Test the Service locally with tests defined in the mlx.toml")]
     Run {
@@ -142,40 +252,98 @@ enum ServeActions {
         test: Option<String>,
         #[arg(long, help = "Run test call remotely", default_value = "false")]
         remote: bool,
+        #[arg(long, help = "Run pdm install even when the venv looks up to date")]
+        reinstall: bool,
+        #[arg(
+            long,
+            help = "Run each selected test N times and report latency stats",
+            default_value_t = 1
+        )]
+        repeat: u32,
+        #[arg(
+            long,
+            help = "Read the request body from a JSON file instead of the mlx.toml tests"
+        )]
+        body_file: Option<String>,
+        #[arg(
+            long,
+            help = "Publish up to N local test messages at a time",
+            default_value_t = 1
+        )]
+        concurrency: u32,
+        #[arg(long, help = "Follow the job's logs after a remote call")]
+        logs: bool,
+        #[arg(
+            long,
+            help = "Run up to N whole tests concurrently and summarize pass/fail",
+            default_value_t = 1
+        )]
+        parallel: u32,
+    },
+    #[command(about = "Send one ad-hoc JSON request to a deployed service")]
+    Call {
+        #[arg(help = "Name of the service (defaults to the local mlx.toml when omitted)")]
+        name: Option<String>,
+        #[arg(help = "Request body as inline JSON, or @file.json to read from a file")]
+        body: String,
     },
     #[command(about = "Deploy the server to a service")]
-    Deploy,
-    // (DeployServiceConf),
+    Deploy(DeployServiceConf),
+    #[command(about = "Show the full spec of one deployed service version")]
+    Get {
+        #[arg(help = "Name of the service (defaults to the local mlx.toml when omitted)")]
+        name: Option<String>,
+        #[arg(help = "Version of the service")]
+        version: u32,
+    },
     #[command(about = "List the available services")]
     Ls {
         #[arg(help = "Name of the service")]
         name: Option<String>,
         #[arg(long, help = "Show only the service pointers", default_value = "false")]
         pointers: bool,
+        #[arg(long, help = "Print the raw JSON response instead of a table")]
+        json: bool,
     },
     #[command(about = "Remove a service")]
     Rm {
-        #[arg(help = "Name of the service")]
-        name: String,
+        #[arg(help = "Name of the service (defaults to the local mlx.toml when omitted)")]
+        name: Option<String>,
         #[arg(
-            help = "Optional version of the service - will delete all under name if not specified"
+            value_delimiter = ',',
+            help = "Versions of the service to delete (comma-separated or repeated) - required unless --all"
         )]
-        version: Option<u32>,
+        versions: Vec<u32>,
         #[arg(
             long,
             help = "Force delete all versions of the service",
             default_value = "false"
         )]
         all: bool,
+        #[arg(long, help = "Skip the typed confirmation when deleting all versions")]
+        yes: bool,
     },
     #[command(about = "Scale the service")]
     Scale(ScaleServiceConf),
+    #[command(about = "Check schema.json and mlx.toml together without deploying")]
+    Validate,
+    #[command(about = "Fetch the deployed schema and write it to schema.json")]
+    PullSchema {
+        #[arg(help = "Name of the service (defaults to the local mlx.toml when omitted)")]
+        name: Option<String>,
+        #[arg(help = "Optional version of the service - defaults to the latest")]
+        version: Option<u32>,
+        #[arg(long, help = "Print the schema instead of writing schema.json")]
+        stdout: bool,
+        #[arg(long, help = "Overwrite an existing schema.json without asking")]
+        force: bool,
+    },
     #[command(about = "View the logs of a service")]
     Logs {
-        #[arg(help = "Name of the service")]
-        name: String,
+        #[arg(help = "Name of the service (defaults to the local mlx.toml when omitted)")]
+        name: Option<String>,
         #[arg(help = "Job ID of the service")]
-        job_id: String,
+        job_id: Option<String>,
         #[arg(
             long,
             help = "Include validated input in the logs",
@@ -192,31 +360,171 @@ enum ServeActions {
         logs: bool,
         #[arg(long, help = "Include timer information", default_value_t = false)]
         timer: bool,
+        #[arg(
+            long,
+            help = "Show only the validated input section (sugar over --input)"
+        )]
+        input_only: bool,
+        #[arg(
+            long,
+            help = "Show only the service response section (sugar over --response)"
+        )]
+        response_only: bool,
+        #[arg(long, help = "Write the sections as one structured JSON document")]
+        export_json: Option<String>,
+        #[arg(long, help = "Write the raw log payload to a file (.json or .txt)")]
+        export: Option<String>,
+        #[arg(long, help = "Show log lines newest-first instead of chronological")]
+        reverse: bool,
+        #[arg(long, help = "Show elapsed time as raw milliseconds")]
+        raw: bool,
+        #[arg(
+            long,
+            help = "Poll for new log lines until the job ends (like kubectl logs -f)"
+        )]
+        follow: bool,
+        #[arg(
+            long,
+            value_enum,
+            help = "Timezone used to display timestamps",
+            default_value = "local"
+        )]
+        tz: TzDisplay,
+    },
+    #[command(about = "Show the health of a single service")]
+    Status {
+        #[arg(help = "Name of the service (defaults to the local mlx.toml when omitted)")]
+        name: Option<String>,
+        #[arg(long, help = "Refresh the view on an interval until Ctrl-C")]
+        watch: bool,
+        #[arg(long, help = "Refresh interval in seconds", default_value_t = 5)]
+        interval: u64,
+    },
+    #[command(about = "Generate typed Rust client structs for a service")]
+    Codegen {
+        #[arg(help = "Name of the service (defaults to the local mlx.toml when omitted)")]
+        name: Option<String>,
+        #[arg(long, help = "Write the generated module to a file instead of stdout")]
+        out: Option<String>,
+    },
+    #[command(about = "Show cluster events for a service")]
+    Events {
+        #[arg(help = "Name of the service (defaults to the local mlx.toml when omitted)")]
+        name: Option<String>,
+        #[arg(long, help = "Re-poll the events on an interval until Ctrl-C")]
+        follow: bool,
+        #[arg(long, help = "Poll interval in seconds", default_value_t = 5)]
+        interval: u64,
+    },
+    #[command(about = "Aggregate resource overview of the heaviest services")]
+    Top {
+        #[arg(
+            long,
+            value_enum,
+            help = "Resource to sort by (descending)",
+            default_value = "cpu"
+        )]
+        sort: serve::TopSort,
+        #[arg(long, help = "Continuously refresh the view")]
+        watch: bool,
+        #[arg(long, help = "Refresh interval in seconds", default_value_t = 5)]
+        interval: u64,
     },
     #[command(about = "View the jobs of a service")]
     Jobs {
-        #[arg(help = "Name of the service")]
-        name: String,
+        #[arg(help = "Name of the service (defaults to the local mlx.toml when omitted)")]
+        name: Option<String>,
+        #[arg(long, help = "Page number to fetch (server-side pagination)")]
+        page: Option<u32>,
+        #[arg(long, help = "Number of jobs per page")]
+        page_size: Option<u32>,
+        #[arg(long, help = "Refresh the table until all jobs have ended")]
+        watch: bool,
+        #[arg(long, value_enum, help = "Only show jobs in this state")]
+        status: Option<serve::JobStatusFilter>,
+        #[arg(long, help = "Show at most N jobs (after sorting newest first)")]
+        limit: Option<usize>,
+        #[arg(
+            long,
+            help = "Seconds between refreshes when watching",
+            default_value_t = 5
+        )]
+        interval: u64,
+        #[arg(
+            long,
+            value_enum,
+            help = "Timezone used to display timestamps",
+            default_value = "local"
+        )]
+        tz: TzDisplay,
+        #[arg(long, help = "Show elapsed time as raw milliseconds")]
+        raw: bool,
     },
 }
 
+// Whether the invoked command promises machine-readable stdout
+// (`deploy --output json`, `serve ls --json`), in which case diagnostics
+// must stay off it.
+fn stdout_is_machine_readable(command: &Commands) -> bool {
+    match command {
+        Commands::Serve { action } => match action {
+            ServeActions::Deploy(deploy_conf) => {
+                matches!(deploy_conf.output, serve::DeployOutput::Json)
+            }
+            ServeActions::Ls { json, .. } => *json,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::registry()
-        .with(fmt::layer().with_writer(std::io::stdout))
-        .with(EnvFilter::new(
-            std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
-        ))
-        .init();
-
     let cli = Cli::parse();
 
+    // The CLI's own diagnostics can be emitted as JSON lines for log
+    // collectors; the RUST_LOG level filter applies to both formats.
+    // Commands that promise machine-readable stdout get their diagnostics
+    // on stderr so the payload stays parseable.
+    let registry = tracing_subscriber::registry().with(EnvFilter::new(
+        std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
+    ));
+    let writer = if stdout_is_machine_readable(&cli.command) {
+        fmt::writer::BoxMakeWriter::new(std::io::stderr)
+    } else {
+        fmt::writer::BoxMakeWriter::new(std::io::stdout)
+    };
+    match cli.log_format {
+        CliLogFormat::Human => registry.with(fmt::layer().with_writer(writer)).init(),
+        CliLogFormat::Json => registry
+            .with(fmt::layer().json().with_writer(writer))
+            .init(),
+    }
+
+    serve::set_trace_http(cli.trace_http);
+    serve::set_no_interactive(cli.no_interactive);
+    serve::set_quiet(cli.quiet);
+
+    if let Some(server) = &cli.server {
+        if let Err(e) = serve::set_server_url(server) {
+            error!("Failed to apply --server: {:?}", e);
+            return;
+        }
+    }
+
     debug!("Check debug level");
-    check_for_update().await;
+    // Update check runs concurrently with the user's command so GitHub
+    // latency never sits on the hot path. The result is reported after
+    // the command finishes; we never re-exec mid-command.
+    let update_handle = tokio::spawn(check_for_update(cli.no_update));
 
     match &cli.command {
         Commands::Train { action } => match action {
-            TrainActions::New { name } => {
+            TrainActions::New {
+                name,
+                git_ref,
+                run_hooks,
+            } => {
                 info!("Creating new training experiment: {}", name);
 
                 let target_path = Path::new(&name);
@@ -228,20 +536,12 @@ async fn main() {
                 }
 
                 // Clone the repository
-                let status = Command::new("git")
-                    .arg("clone")
-                    .arg(TRAIN_REPO_URL)
-                    .arg(target_path)
-                    .status()
-                    .expect("Failed to execute git command");
-
-                if !status.success() {
-                    eprintln!("Failed to clone repository");
+                if !clone_with_retry(TRAIN_REPO_URL, target_path, git_ref) {
                     return;
                 }
 
                 // Check if Python 3.11 is installed, if not install it
-                py_env_checker(false);
+                py_env_checker(false, false);
 
                 // Change to the newly cloned repo directory
                 std::env::set_current_dir(target_path).expect("Failed to change directory");
@@ -253,6 +553,8 @@ async fn main() {
                     .status()
                     .expect("Failed to install project dependencies");
 
+                run_postcreate_hook(*run_hooks);
+
                 info!("Setup complete for {}", name);
             }
             TrainActions::Bind => {
@@ -260,18 +562,14 @@ async fn main() {
 
                 assert_files_exist(vec![SCRIPT_PATH, CONFIG_PATH]);
 
-                py_env_checker(false);
+                py_env_checker(false, false);
 
                 run_python_script("main.py", Some(&["--gen-bindings", "1"]));
             }
             TrainActions::Run => {
                 info!("Running the training experiment locally");
 
-                assert_files_exist(vec!["main.py", "pyproject.toml"]);
-
-                py_env_checker(false);
-
-                run_python_script("main.py", Some(&["--gen-bindings", "0"]));
+                run_local_job(false, false, &["--gen-bindings", "0"]);
             }
             TrainActions::Launch {
                 ray_address,
@@ -279,80 +577,112 @@ async fn main() {
             } => {
                 info!("Launching training experiment on remote Ray cluster");
 
-                assert_files_exist(vec!["main.py", "pyproject.toml"]);
-
-                py_env_checker(false);
-
-                run_python_script(
-                    "main.py",
-                    Some(&[
+                run_local_job(
+                    false,
+                    false,
+                    &[
                         "--gen-bindings",
                         "0",
                         "--ray-address",
                         ray_address,
                         "--prepare-batches",
                         &prepare_batches.to_string(),
-                    ]),
+                    ],
                 );
             }
         },
         Commands::Xp { action } => match action {
             XpActions::Ls => {
-                println!("Listing remote experiments");
-                // Implement the logic to list experiments run remotely
+                info!("Listing remote experiments");
+
+                if let Err(report) = xp::list_experiments() {
+                    error!("Failed to list experiments: {:?}", report);
+                }
             }
-            XpActions::Logs { name, run } => {
+            XpActions::Logs { name, run, format } => {
                 info!("Streaming logs for experiment {} run {}", name, run);
 
                 let result = tokio::runtime::Builder::new_current_thread()
                     .enable_all()
                     .build()
                     .unwrap()
-                    .block_on(stream_logs());
+                    .block_on(stream_logs(name, run, *format));
 
                 if let Err(e) = result {
                     println!("Error occurred: {:?}", e);
                 }
             }
             XpActions::Board { name, run } => {
-                println!(
-                    "Displaying live TensorBoard for experiment {} run {}",
-                    name, run
-                );
-                // Implement the logic to show live TensorBoard
+                let result = xp::launch_board(name, run);
+                if let Err(e) = result {
+                    println!("Error occurred: {:?}", e);
+                }
             }
-            XpActions::Ray => {
-                println!("Displaying Ray cluster monitor");
-                // Implement the logic to view Ray jobs, logs, and metrics
+            XpActions::Ray { ray_address } => {
+                if let Err(e) = xp::open_ray_dashboard(ray_address) {
+                    error!("Failed to open the Ray dashboard: {:?}", e);
+                }
             }
         },
         Commands::Data { action } => match action {
             DataActions::Show => {
-                println!("Displaying filesystem structure of shared NFS");
-                // Implement the logic to show filesystem structure
+                let result = data::show_data_fs();
+                if let Err(e) = result {
+                    println!("Error occurred: {:?}", e);
+                }
             }
             DataActions::New => {
                 println!("Creating new data job folder from template");
                 // Implement the logic to create a new data job folder
             }
             DataActions::Run => {
-                println!("Running data job locally");
-                // Implement the logic to run data job locally
+                info!("Running data job locally");
+
+                // Data jobs share the training template layout; a missing
+                // main.py means we're not inside a data-job folder at all.
+                if !Path::new(SCRIPT_PATH).exists() {
+                    error!(
+                        "No {} in the current directory - run this inside a data-job folder",
+                        SCRIPT_PATH
+                    );
+                    return;
+                }
+
+                run_local_job(false, false, &["--data-job", "1"]);
             }
             DataActions::Launch { ray_address } => {
-                println!("Launching data job on remote Ray cluster");
-                if let Some(address) = ray_address {
-                    println!("Ray address: {}", address);
+                info!("Launching data job on remote Ray cluster");
+
+                // Match training: an omitted address falls back to "auto"
+                // and lets Ray discover the cluster.
+                let ray_address = ray_address.as_deref().unwrap_or(RAY_ADDRESS);
+                if ray_address != "auto" && reqwest::Url::parse(ray_address).is_err() {
+                    error!(
+                        "Invalid ray address '{}' - pass a URL or the literal \"auto\"",
+                        ray_address
+                    );
+                    return;
                 }
-                // Implement the logic to launch the data job on a remote Ray cluster
+
+                run_local_job(
+                    false,
+                    false,
+                    &["--data-job", "1", "--ray-address", ray_address],
+                );
             }
-            DataActions::Rm => {
-                println!("Removing folder from shared NFS");
-                // Implement the logic to remove a folder from the shared NFS
+            DataActions::Rm { path, yes } => {
+                let result = data::remove_data_folder(path, *yes);
+                if let Err(e) = result {
+                    println!("Error occurred: {:?}", e);
+                }
             }
         },
         Commands::Serve { action } => match action {
-            ServeActions::New { name } => {
+            ServeActions::New {
+                name,
+                git_ref,
+                run_hooks,
+            } => {
                 info!("Creating new service: {}", name);
 
                 let target_path = Path::new(&name);
@@ -361,12 +691,11 @@ async fn main() {
                     "Cloning the training repo to {}",
                     target_path.to_str().unwrap()
                 );
-                let _ = run_command(
-                    "git",
-                    &["clone", PY_INF_REPO_URL, target_path.to_str().unwrap()],
-                );
+                if !clone_with_retry(PY_INF_REPO_URL, target_path, git_ref) {
+                    return;
+                }
                 // Check if Python 3.11 is installed, if not install it
-                py_env_checker(false);
+                py_env_checker(false, false);
 
                 // Change to the newly cloned repo directory
                 std::env::set_current_dir(target_path).expect("Failed to change directory");
@@ -375,9 +704,20 @@ async fn main() {
                 info!("Installing project dependencies...");
                 let _ = run_command("pdm", &["install"]);
 
+                run_postcreate_hook(*run_hooks);
+
                 info!("Setup complete for {}", name);
             }
-            ServeActions::Run { test, remote } => {
+            ServeActions::Run {
+                test,
+                remote,
+                reinstall,
+                repeat,
+                body_file,
+                concurrency,
+                logs,
+                parallel,
+            } => {
                 if !remote {
                     info!("Running Service locally");
                 } else {
@@ -392,15 +732,32 @@ async fn main() {
                 ]);
 
                 if !remote {
-                    py_env_checker(true);
+                    py_env_checker(true, *reinstall);
                     run_python_script("main.py", Some(&["--build", "1"]));
                     assert_files_exist(vec![SERVICE_CONFIG_PATH]);
                 }
 
-                let res = run_tests(test.clone(), *remote).await;
-                res.unwrap();
+                let res = run_tests(
+                    test.clone(),
+                    *remote,
+                    *repeat,
+                    body_file.clone(),
+                    *concurrency,
+                    *logs,
+                    *parallel,
+                )
+                .await;
+                if let Err(e) = res {
+                    error!("Test run failed: {:?}", e);
+                    std::process::exit(1);
+                }
             }
-            ServeActions::Deploy => {
+            ServeActions::Call { name, body } => {
+                if let Err(e) = serve::call_service(name.clone(), body) {
+                    error!("Failed to call service: {:?}", e);
+                }
+            }
+            ServeActions::Deploy(deploy_conf) => {
                 info!("Deploying the Service to a MLX cluster...");
 
                 assert_files_exist(vec![
@@ -411,12 +768,6 @@ async fn main() {
                     SERVICE_DOCKERFILE_PATH,
                 ]);
 
-                py_env_checker(false);
-
-                run_python_script("main.py", Some(&["--build", "1"]));
-
-                assert_files_exist(vec![SERVICE_CONFIG_PATH]);
-
                 let conf: TomlConfig = {
                     let toml_data = std::fs::read_to_string(SERVICE_TOML_PATH)
                         .expect("Failed to read mlx.toml file");
@@ -425,23 +776,83 @@ async fn main() {
                     conf
                 };
 
-                let _ = deploy_service(&conf).await;
+                // Deploying to prod requires a typed confirmation of the
+                // service name before any build work starts.
+                if conf.is_prod_stage() && !deploy_conf.yes && !confirm_prod_deploy(conf.service())
+                {
+                    error!("Prod deploy aborted");
+                    return;
+                }
+
+                py_env_checker(false, false);
+
+                if deploy_conf.quiet_build {
+                    if !run_quiet_build() {
+                        std::process::exit(1);
+                    }
+                } else {
+                    run_python_script("main.py", Some(&["--build", "1"]));
+                }
+
+                assert_files_exist(vec![SERVICE_CONFIG_PATH]);
+
+                let _ = deploy_service(&conf, deploy_conf).await;
+            }
+            ServeActions::Get { name, version } => {
+                if let Err(e) = serve::get_service(name.clone(), *version) {
+                    error!("Failed to get service: {:?}", e);
+                }
             }
-            ServeActions::Ls { name, pointers } => {
-                info!("Listing available services");
+            ServeActions::Ls {
+                name,
+                pointers,
+                json,
+            } => {
+                if !*json {
+                    info!("Listing available services");
+                }
 
-                let _ = list_services(name.as_deref(), *pointers);
+                let _ = list_services(name.as_deref(), *pointers, *json);
             }
-            ServeActions::Rm { name, version, all } => {
-                if let Some(version) = version {
-                    info!("Removing service {} version {}", name, version);
-                    let _ = delete_service(name, Some(*version));
+            ServeActions::Rm {
+                name,
+                versions,
+                all,
+                yes,
+            } => {
+                // A lone numeric positional reads as the version list, with
+                // the service name defaulting from the local mlx.toml -
+                // mirroring how the Logs arm treats a lone job id.
+                let (name, versions) = match (name.clone(), versions.clone()) {
+                    (Some(first), versions) if versions.is_empty() && !*all => {
+                        match first
+                            .split(',')
+                            .map(str::parse::<u32>)
+                            .collect::<Result<Vec<u32>, _>>()
+                        {
+                            Ok(parsed) => (None, parsed),
+                            Err(_) => (Some(first), versions),
+                        }
+                    }
+                    (name, versions) => (name, versions),
+                };
+
+                let name = match serve::resolve_service_name(name).await {
+                    Ok(name) => name,
+                    Err(report) => {
+                        error!("Failed to resolve service name: {:?}", report);
+                        return;
+                    }
+                };
+
+                if !versions.is_empty() {
+                    info!("Removing service {} version(s) {:?}", name, versions);
+                    let _ = delete_versions(&name, &versions);
                 } else {
                     if !all {
                         error!("Please specify a version to remove or use the --all flag to remove all versions of the service");
                     } else {
-                        info!("Removing all versions of service {}", name);
-                        let _ = delete_service(name, None);
+                        let _ = serve::delete_all_versions(&name, *yes);
                     }
                 }
             }
@@ -457,22 +868,328 @@ async fn main() {
                 response,
                 logs,
                 timer,
+                input_only,
+                response_only,
+                export_json,
+                export,
+                reverse,
+                raw,
+                follow,
+                tz,
             } => {
+                // A single positional reads as the job id, with the service
+                // name defaulting from the local mlx.toml.
+                let (name, job_id) = match (name.clone(), job_id.clone()) {
+                    (Some(name), Some(job_id)) => (name, job_id),
+                    (Some(only), None) => {
+                        let name = match serve::resolve_service_name(None).await {
+                            Ok(name) => name,
+                            Err(report) => {
+                                error!("Failed to resolve service name: {:?}", report);
+                                return;
+                            }
+                        };
+                        (name, only)
+                    }
+                    _ => {
+                        error!("A job id is required");
+                        return;
+                    }
+                };
+
                 info!("Viewing logs for service: {} with job_id: {}", name, job_id);
 
-                let resp = log_service(name, job_id, *input, *response, *logs, *timer);
-                resp.unwrap();
+                // The *-only flags are sugar over the include flags; any
+                // explicit include flag wins over them.
+                let (input, response, logs, timer) = if *input || *response || *logs || *timer {
+                    (*input, *response, *logs, *timer)
+                } else if *input_only {
+                    (true, false, false, false)
+                } else if *response_only {
+                    (false, true, false, false)
+                } else {
+                    (false, false, false, false)
+                };
+
+                if let Err(report) = log_service(
+                    &name,
+                    &job_id,
+                    input,
+                    response,
+                    logs,
+                    timer,
+                    *tz,
+                    export_json.clone(),
+                    export.clone(),
+                    *reverse,
+                    *raw,
+                    *follow,
+                ) {
+                    error!("Failed to get logs: {:?}", report);
+                }
+            }
+            ServeActions::Status {
+                name,
+                watch,
+                interval,
+            } => {
+                let _ = status_service(name.clone(), *watch, *interval);
+            }
+            ServeActions::Validate => {
+                serve::validate_service();
+            }
+            ServeActions::PullSchema {
+                name,
+                version,
+                stdout,
+                force,
+            } => {
+                let _ = pull_schema_service(name.clone(), *version, *stdout, *force);
+            }
+            ServeActions::Codegen { name, out } => {
+                let _ = serve::codegen_service(name.clone(), out.clone());
+            }
+            ServeActions::Events {
+                name,
+                follow,
+                interval,
+            } => {
+                let _ = serve::events_service(name.clone(), *follow, *interval);
             }
-            ServeActions::Jobs { name } => {
+            ServeActions::Top {
+                sort,
+                watch,
+                interval,
+            } => {
+                let _ = serve::top_services(*sort, *watch, *interval);
+            }
+            ServeActions::Jobs {
+                name,
+                page,
+                page_size,
+                watch,
+                status,
+                limit,
+                interval,
+                tz,
+                raw,
+            } => {
+                let name = match serve::resolve_service_name(name.clone()).await {
+                    Ok(name) => name,
+                    Err(report) => {
+                        error!("Failed to resolve service name: {:?}", report);
+                        return;
+                    }
+                };
+
                 info!("Viewing jobs for service {}", name);
 
-                let _ = jobs_service(name);
+                let _ = jobs_service(
+                    &name, *tz, *page, *page_size, *watch, *interval, *status, *limit, *raw,
+                );
             }
         },
+        Commands::Doctor => {
+            run_doctor();
+        }
+        Commands::Update => {
+            run_update().await;
+        }
+    }
+
+    // Report the background update check once the command is done. On
+    // throttled days this resolves instantly. The check only ever
+    // notifies; installing is an explicit `mlx update`.
+    if let Ok(true) = update_handle.await {
+        info!("A new version of mlx-client is available - run `mlx update` to install it");
+    }
+}
+
+// Bounded retry around git clone for the scaffolding commands: transient
+// network failures otherwise leave a half-cloned directory behind and
+// abort. The partial directory is removed between attempts. The requested
+// ref (branch, tag or sha) is checked out after the clone so scaffolds can
+// pin a known-good template version.
+fn clone_with_retry(repo_url: &str, target_path: &Path, git_ref: &str) -> bool {
+    static CLONE_ATTEMPTS: u32 = 3;
+
+    // Refuse to touch a directory we didn't create: cleaning up between
+    // attempts must never delete pre-existing user data.
+    if target_path.exists() {
+        error!(
+            "Destination {} already exists - remove it or pick another name",
+            target_path.display()
+        );
+        return false;
+    }
+
+    for attempt in 1..=CLONE_ATTEMPTS {
+        let status = Command::new("git")
+            .arg("clone")
+            .arg(repo_url)
+            .arg(target_path)
+            .status();
+
+        match status {
+            Ok(status) if status.success() => return checkout_ref(repo_url, target_path, git_ref),
+            _ => {
+                // Drop whatever the failed clone left behind so the next
+                // attempt starts clean.
+                if target_path.exists() {
+                    let _ = std::fs::remove_dir_all(target_path);
+                }
+
+                if attempt < CLONE_ATTEMPTS {
+                    warn!(
+                        "git clone failed (attempt {}/{}), retrying...",
+                        attempt, CLONE_ATTEMPTS
+                    );
+                    std::thread::sleep(std::time::Duration::from_secs(2 * attempt as u64));
+                }
+            }
+        }
+    }
+
+    error!(
+        "Failed to clone {} after {} attempts - check your network and retry",
+        repo_url, CLONE_ATTEMPTS
+    );
+    false
+}
+
+// Templates can ship a mlx-postcreate.sh for last-mile setup (pre-commit
+// install, model downloads). Because the hook runs arbitrary code it only
+// executes with --run-hooks or an explicit confirmation; no hook means a
+// silent no-op. Expects the cwd to be the freshly scaffolded project.
+fn run_postcreate_hook(run_hooks: bool) {
+    let hook = "mlx-postcreate.sh";
+    if !Path::new(hook).exists() {
+        return;
+    }
+
+    if !run_hooks
+        && !serve::confirm(&format!(
+            "Template ships a {} setup hook - run it? [y/N]: ",
+            hook
+        ))
+    {
+        info!("Skipping {}", hook);
+        return;
+    }
+
+    info!("Running {}...", hook);
+    let _ = run_command("bash", &[hook]);
+}
+
+fn checkout_ref(repo_url: &str, target_path: &Path, git_ref: &str) -> bool {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(target_path)
+        .arg("checkout")
+        .arg(git_ref)
+        .status();
+
+    match status {
+        Ok(status) if status.success() => true,
+        _ => {
+            error!(
+                "Ref '{}' does not exist in {} - check the branch/tag/sha and retry",
+                git_ref, repo_url
+            );
+            let _ = std::fs::remove_dir_all(target_path);
+            false
+        }
+    }
+}
+
+// Environment checks mirroring the preflight checks deploy runs, so
+// problems surface before any long-running command.
+fn run_doctor() {
+    let mut ok = true;
+
+    match serve::ensure_runtime_running(serve::ContainerRuntime::Docker) {
+        Ok(_) => info!("Docker daemon: OK"),
+        Err(report) => {
+            error!("Docker daemon: {:?}", report);
+            ok = false;
+        }
+    }
+
+    match serve::ensure_registry_reachable() {
+        Ok(_) => info!("Image registry: OK"),
+        Err(report) => {
+            error!("Image registry: {:?}", report);
+            ok = false;
+        }
+    }
+
+    if Command::new("python3.11").arg("--version").output().is_ok() {
+        info!("Python 3.11: OK");
+    } else {
+        error!("Python 3.11: not found");
+        ok = false;
+    }
+
+    if Command::new("pdm").arg("--version").output().is_ok() {
+        info!("PDM: OK");
+    } else {
+        error!("PDM: not found");
+        ok = false;
+    }
+
+    if ok {
+        info!("All checks passed");
+    } else {
+        std::process::exit(1);
+    }
+}
+
+// Asks the user to type the service name back before a prod deploy,
+// skippable with --yes.
+fn confirm_prod_deploy(service: &str) -> bool {
+    println!("You are deploying '{}' to the prod stage.", service);
+    match serve::prompt_line("Type the service name to confirm: ") {
+        Some(input) => input == service,
+        None => false,
+    }
+}
+
+// Buffers the Python build output for --quiet-build deploys, discarding it
+// on success and printing it only when the build fails. Returns whether
+// the build succeeded so the deploy can bail out cleanly.
+fn run_quiet_build() -> bool {
+    let output = match Command::new("pdm")
+        .args(["run", "python", "main.py", "--build", "1"])
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => {
+            error!("Failed to run the Python build: {}", e);
+            return false;
+        }
+    };
+
+    if !output.status.success() {
+        eprintln!("{}", String::from_utf8_lossy(&output.stdout));
+        eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+        error!("Python build failed - output above");
+        return false;
     }
+
+    true
+}
+
+// Shared "assert files, check the venv, run main.py" sequence behind the
+// local train/data run and launch paths - the templates share one layout.
+fn run_local_job(install: bool, reinstall: bool, args: &[&str]) {
+    assert_files_exist(vec![SCRIPT_PATH, CONFIG_PATH]);
+
+    py_env_checker(install, reinstall);
+
+    run_python_script(SCRIPT_PATH, Some(args));
 }
 
-fn py_env_checker(install: bool) -> bool {
+fn py_env_checker(install: bool, reinstall: bool) -> bool {
     // Check if Python 3.11 is installed, if not install it
     let python_installed = Command::new("python3.11").arg("--version").output().is_ok();
 
@@ -525,21 +1242,83 @@ fn py_env_checker(install: bool) -> bool {
     info!("Python3.11 & PDM all ok");
 
     if install {
+        // Reinstalling on every run costs seconds; skip when the venv
+        // already matches the lockfile.
+        if !reinstall && venv_up_to_date() {
+            info!("Reusing existing .venv (pdm.lock unchanged) - pass --reinstall to force");
+            return true;
+        }
+
         info!("Installing PDM dependencies");
 
         Command::new("pdm")
             .arg("install")
             .status()
             .unwrap_or_else(|_| panic!("IF THIS FAILS, YOUR PYTHON SETUP IS UNIQUE TO ALL OTHER WONDERA MACHINE - CALL ALE TO SUPPORT YOUR SETUP"));
+
+        store_lock_hash();
     }
 
     return true;
 }
 
-async fn check_for_update() {
-    info!("Checking mlx-client for updates ...");
+// The stored lockfile hash lives inside the venv so deleting the venv
+// also invalidates the skip.
+fn lock_hash() -> Option<String> {
+    use std::hash::{Hash, Hasher};
+
+    let contents = std::fs::read("pdm.lock").ok()?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    contents.hash(&mut hasher);
+
+    Some(format!("{:x}", hasher.finish()))
+}
+
+fn venv_up_to_date() -> bool {
+    if !Path::new(".venv").exists() {
+        return false;
+    }
+
+    match (lock_hash(), std::fs::read_to_string(".venv/.mlx-lock-hash")) {
+        (Some(current), Ok(stored)) => current == stored.trim(),
+        _ => false,
+    }
+}
+
+fn store_lock_hash() {
+    if let Some(hash) = lock_hash() {
+        let _ = std::fs::write(".venv/.mlx-lock-hash", hash);
+    }
+}
 
-    let latest_hash = fetch_latest_commit_hash().await.unwrap();
+// Returns true when a newer commit exists upstream. Never installs
+// anything - that is `mlx update`'s job - and is skippable via
+// --no-update / MLX_NO_UPDATE_CHECK for air-gapped or scripted use.
+async fn check_for_update(no_update: bool) -> bool {
+    if no_update {
+        debug!("Update check disabled, skipping");
+        return false;
+    }
+
+    // At most one check per day - the throttle file's mtime records the
+    // last attempt so every other invocation skips the network entirely.
+    if !update_check_due() {
+        debug!("Update check already ran today, skipping");
+        return false;
+    }
+
+    debug!("Checking mlx-client for updates ...");
+
+    // A slow or unreachable GitHub must never hold up the user's command,
+    // so the fetch is capped by a short configurable timeout and any
+    // failure silently skips the update.
+    let latest_hash = match fetch_latest_commit_hash().await {
+        Ok(hash) => hash,
+        Err(e) => {
+            debug!("Skipping update check: {}", e);
+            return false;
+        }
+    };
 
     let current_hash = match read_current_commit_hash() {
         Ok(hash) => hash,
@@ -549,36 +1328,56 @@ async fn check_for_update() {
     debug!("Current hash: {}", current_hash);
     debug!("Latest hash: {}", latest_hash);
 
-    if latest_hash != current_hash {
-        info!("New version of mlx-client detected :) updating...");
-        // Run the install.sh script to update
-        std::process::Command::new("bash")
-            .arg("-c")
-            .arg("curl -sSL https://raw.githubusercontent.com/Wondera-AI/mlx-client/main/install.sh | bash")
-            .status()
-            .expect("Failed to update");
-
-        write_current_commit_hash(&latest_hash).expect("Failed to write the latest commit hash");
-
-        info!("Update complete, running mlx command now...");
-
-        // let args: Vec<String> = std::env::args().skip(1).collect();
-        // let args_str: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-        // let _ = run_command("mlx", &args_str);
-    }
+    latest_hash != current_hash
 }
 
 async fn fetch_latest_commit_hash() -> Result<String, Box<dyn std::error::Error>> {
     let url = CLIENT_REPO_URL;
-    let client = reqwest::Client::new();
-    let response = client
-        .get(url)
-        .header("User-Agent", "mlx-client")
-        .send()
-        .await?;
+    let timeout = std::time::Duration::from_secs(config::CLIENT_CONFIG.update_check_timeout_secs);
+    let client = reqwest::Client::builder().timeout(timeout).build()?;
+    let mut request = client.get(url).header("User-Agent", "mlx-client");
+
+    // An optional token raises the GitHub rate limit, which matters for
+    // shared CI runners.
+    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+        if !token.is_empty() {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+    }
+
+    let response = request.send().await?;
     let json: serde_json::Value = response.json().await?;
 
-    Ok(json["sha"].as_str().unwrap().to_string())
+    // Rate-limit error bodies carry no sha field; surface that as an error
+    // the caller logs and skips instead of panicking before the user's
+    // command even runs.
+    json["sha"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| format!("no sha in GitHub response: {}", json).into())
+}
+
+// True when no check has run yet today. Touches the throttle file so a
+// failed fetch still counts as the day's attempt.
+fn update_check_due() -> bool {
+    let Ok(mut path) = get_hash_file_path() else {
+        return true;
+    };
+    path.set_file_name(".update_check");
+
+    let due = match std::fs::metadata(&path).and_then(|meta| meta.modified()) {
+        Ok(last_check) => match last_check.elapsed() {
+            Ok(elapsed) => elapsed >= std::time::Duration::from_secs(24 * 60 * 60),
+            Err(_) => true,
+        },
+        Err(_) => true,
+    };
+
+    if due {
+        let _ = std::fs::write(&path, "");
+    }
+
+    due
 }
 
 fn get_hash_file_path() -> std::io::Result<std::path::PathBuf> {
@@ -596,6 +1395,57 @@ fn get_hash_file_path() -> std::io::Result<std::path::PathBuf> {
     Ok(config_dir)
 }
 
+// Deliberate, prompted upgrade: show current vs latest, confirm, run the
+// installer, then record the installed hash. The startup check never gets
+// here on its own.
+async fn run_update() {
+    let latest_hash = match fetch_latest_commit_hash().await {
+        Ok(hash) => hash,
+        Err(e) => {
+            error!("Failed to fetch the latest version: {}", e);
+            return;
+        }
+    };
+    let current_hash = read_current_commit_hash().unwrap_or_default();
+
+    info!(
+        "Current version: {}",
+        if current_hash.is_empty() {
+            "unknown"
+        } else {
+            &current_hash
+        }
+    );
+    info!("Latest version:  {}", latest_hash);
+
+    if latest_hash == current_hash {
+        info!("Already up to date");
+        return;
+    }
+
+    if !serve::confirm("Install the latest version? [y/N]: ") {
+        info!("Aborted - nothing installed");
+        return;
+    }
+
+    // The replaced binary only affects the next invocation; this process
+    // keeps running the code it started with.
+    let status = Command::new("bash")
+        .arg("-c")
+        .arg("curl -sSL https://raw.githubusercontent.com/Wondera-AI/mlx-client/main/install.sh | bash")
+        .status();
+
+    match status {
+        Ok(status) if status.success() => {
+            if let Err(e) = write_current_commit_hash(&latest_hash) {
+                debug!("Installed, but failed to record the version: {}", e);
+            }
+            info!("Updated - the new binary takes effect on your next run");
+        }
+        _ => error!("Install failed - see the installer output above"),
+    }
+}
+
 fn read_current_commit_hash() -> std::io::Result<String> {
     let hash_file_path = get_hash_file_path()?;
     if let Ok(hash) = std::fs::read_to_string(&hash_file_path) {
@@ -0,0 +1,116 @@
+use crate::serve::{get_server_base_url, resolve_service_name, DType, ServiceParams, HTTP_CLIENT};
+use crate::SERVICE_CONFIG_PATH;
+use utils::prelude::*;
+
+// Fires one ad-hoc request at a deployed service without touching the
+// mlx.toml test specs. The body is inline JSON or @file.json; when a
+// local schema.json is present the body is validated against it first.
+#[tokio::main]
+pub async fn call_service(service_name: Option<String>, body: &str) -> RResult<(), AnyErr2> {
+    let service_name = resolve_service_name(service_name).await?;
+
+    let body = parse_body(body)?;
+
+    // Best-effort preflight: outside a service directory there is no
+    // schema to check against, and the server validates anyway. The local
+    // schema only describes the local service, so skip it when calling a
+    // different one by name.
+    if local_service_name().as_deref() == Some(service_name.as_str())
+        && std::path::Path::new(SERVICE_CONFIG_PATH).exists()
+    {
+        let schema_json = std::fs::read_to_string(SERVICE_CONFIG_PATH)
+            .change_context(err2!("Failed to read service schema file"))?;
+        let params = ServiceParams::from_json(&schema_json)?;
+        validate_body(&params, &body)?;
+    } else {
+        debug!(
+            "No local schema for service '{}' - skipping validation",
+            service_name
+        );
+    }
+
+    let url = format!(
+        "{}/handle_request/{}",
+        get_server_base_url().await?,
+        service_name
+    );
+    debug!("POST to url: {} with body data: {}", url, body);
+
+    let res = HTTP_CLIENT
+        .post(url)
+        .header("Content-Type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .change_context(err2!("Failed handle_request call"))?;
+
+    info!("Service Response Status: {}", res.status());
+
+    let response_body = res.text().await.unwrap_or_else(|e| {
+        debug!("Failed to read response body: {:?}", e);
+        "Error reading body".to_string()
+    });
+    println!("{}", response_body);
+
+    Ok(())
+}
+
+// The service name declared by the local mlx.toml, if run inside a
+// service directory.
+fn local_service_name() -> Option<String> {
+    let toml_data = std::fs::read_to_string(crate::SERVICE_TOML_PATH).ok()?;
+    let conf: toml::Value = toml::from_str(&toml_data).ok()?;
+    conf.get("service")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+}
+
+// Inline JSON, or the contents of a file when prefixed with '@'.
+fn parse_body(raw: &str) -> RResult<serde_json::Value, AnyErr2> {
+    let contents = match raw.strip_prefix('@') {
+        Some(path) => std::fs::read_to_string(path)
+            .change_context(err2!(format!("Failed to read body file {}", path)))?,
+        None => raw.to_string(),
+    };
+
+    serde_json::from_str(&contents).change_context(err2!("Malformed JSON request body"))
+}
+
+// Same dtype/required checks validate_tests applies to mlx.toml specs,
+// but against a serde_json body.
+fn validate_body(params: &ServiceParams, body: &serde_json::Value) -> RResult<(), AnyErr2> {
+    let map = body
+        .as_object()
+        .ok_or_else(|| err2!("Request body must be a JSON object"))?;
+
+    if let Some(body_params) = &params.input.body {
+        for param in body_params {
+            match map.get(&param.name) {
+                Some(value) => {
+                    let matches = match param.dtype {
+                        DType::String => value.is_string(),
+                        DType::Int => value.is_i64() || value.is_u64(),
+                        DType::Float => value.is_number(),
+                        DType::Bool => value.is_boolean(),
+                        DType::List => value.is_array(),
+                    };
+                    if !matches {
+                        return Err(Report::new(err2!(format!(
+                            "Expected '{:?}' for parameter '{}', but found {:?}",
+                            param.dtype, param.name, value
+                        ))));
+                    }
+                }
+                None if param.required => {
+                    return Err(Report::new(err2!(format!(
+                        "Missing required parameter '{}' in the request body",
+                        param.name
+                    ))));
+                }
+                None => {}
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use crate::serve::list::fetch_services;
+use crate::serve::{confirm_overwrite, resolve_service_name, DType, Param, ServiceParams};
+use utils::prelude::*;
+
+// Emits a Rust module with typed request/response structs for a deployed
+// service plus a thin reqwest call() helper, so downstream Rust consumers
+// get a typed SDK instead of hand-rolled serde_json values.
+#[tokio::main]
+pub async fn codegen_service(
+    service_name: Option<String>,
+    out: Option<String>,
+) -> RResult<(), AnyErr2> {
+    let service_name = resolve_service_name(service_name).await?;
+
+    let response = fetch_services(Some(&service_name), false).await?;
+    let services = response
+        .as_array()
+        .ok_or_else(|| err2!("Response is not an array"))?;
+
+    let service = services
+        .iter()
+        .max_by_key(|s| s["version"].as_i64().unwrap_or(0))
+        .ok_or_else(|| Report::new(err2!(format!("Service {} not found", service_name))))?;
+
+    let schema = service
+        .get("service_schema")
+        .ok_or_else(|| err2!("Service record has no service_schema field"))?;
+    let params = ServiceParams::from_value(schema)?;
+
+    let module = generate_module(&service_name, &params);
+
+    match out {
+        Some(path) => {
+            let target = std::path::Path::new(&path);
+            if target.exists() && !confirm_overwrite(&path) {
+                info!("Aborted - {} left untouched", path);
+                return Ok(());
+            }
+
+            std::fs::write(target, &module)
+                .change_context(err2!(format!("Failed to write {}", path)))?;
+            info!("Wrote typed client for {} to {}", service_name, path);
+        }
+        None => print!("{}", module),
+    }
+
+    Ok(())
+}
+
+// Maps the schema dtypes onto Rust types; the DType enum guarantees
+// every schema that parses has a mapping here.
+fn rust_type(dtype: DType) -> &'static str {
+    match dtype {
+        DType::String => "String",
+        DType::Int => "i64",
+        DType::Float => "f64",
+        DType::Bool => "bool",
+        DType::List => "Vec<serde_json::Value>",
+    }
+}
+
+// Keywords that would make the emitted struct fail to compile if a
+// schema param is named after one.
+static RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+];
+
+// Schema param names aren't guaranteed to be valid Rust identifiers:
+// invalid characters become underscores, leading digits get a prefix and
+// keywords are raw-escaped. `self`/`Self`/`super`/`crate` can't even be
+// raw identifiers, so those get an underscore suffix instead.
+fn sanitize_ident(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+
+    match ident.as_str() {
+        "self" | "Self" | "super" | "crate" | "_" => format!("{}_", ident),
+        _ if RUST_KEYWORDS.contains(&ident.as_str()) => format!("r#{}", ident),
+        _ => ident,
+    }
+}
+
+// One struct field per schema param. When the sanitized identifier no
+// longer spells the wire name, a serde rename keeps the payload intact
+// (raw identifiers serialize without the r# already).
+fn field_line(param: &Param) -> String {
+    let ident = sanitize_ident(&param.name);
+    let ty = rust_type(param.dtype);
+
+    let mut line = String::new();
+    if ident.trim_start_matches("r#") != param.name {
+        line.push_str(&format!("    #[serde(rename = \"{}\")]\n", param.name));
+    }
+    if param.required {
+        line.push_str(&format!("    pub {}: {},\n", ident, ty));
+    } else {
+        line.push_str(&format!("    pub {}: Option<{}>,\n", ident, ty));
+    }
+    line
+}
+
+fn generate_module(service_name: &str, params: &ServiceParams) -> String {
+    let mut module = String::new();
+
+    module.push_str(&format!(
+        "// Generated by `mlx serve codegen {}` - do not edit by hand.\n",
+        service_name
+    ));
+    module.push_str("use serde::{Deserialize, Serialize};\n\n");
+
+    module.push_str("#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct Request {\n");
+    if let Some(body) = &params.input.body {
+        for param in body {
+            module.push_str(&field_line(param));
+        }
+    }
+    module.push_str("}\n\n");
+
+    module.push_str("#[derive(Debug, Clone, Serialize, Deserialize)]\npub struct Response {\n");
+    let mut outputs: Vec<&Param> = params.output.values().collect();
+    outputs.sort_by(|a, b| a.name.cmp(&b.name));
+    for param in outputs {
+        module.push_str(&field_line(param));
+    }
+    module.push_str("}\n\n");
+
+    module.push_str(&format!(
+        r#"pub async fn call(
+    client: &reqwest::Client,
+    server_url: &str,
+    request: &Request,
+) -> Result<Response, reqwest::Error> {{
+    client
+        .post(format!("{{}}/handle_request/{}", server_url))
+        .json(request)
+        .send()
+        .await?
+        .json()
+        .await
+}}
+"#,
+        service_name
+    ));
+
+    module
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_module_round_trip() {
+        let schema = serde_json::json!({
+            "input": {
+                "body": [
+                    {"name": "path_image", "dtype": "string", "required": true},
+                    {"name": "smoothing", "dtype": "int", "required": false}
+                ]
+            },
+            "output": [
+                {"name": "label", "dtype": "string", "required": true},
+                {"name": "confidence", "dtype": "float", "required": true}
+            ]
+        });
+
+        let params = ServiceParams::from_value(&schema).expect("Failed to parse schema");
+        let module = generate_module("mnist", &params);
+
+        assert!(module.contains("pub struct Request"));
+        assert!(module.contains("pub path_image: String,"));
+        assert!(module.contains("pub smoothing: Option<i64>,"));
+
+        assert!(module.contains("pub struct Response"));
+        assert!(module.contains("pub label: String,"));
+        assert!(module.contains("pub confidence: f64,"));
+
+        assert!(module.contains("/handle_request/mnist"));
+    }
+
+    #[test]
+    fn test_field_line_sanitizes_identifiers() {
+        let schema = serde_json::json!({
+            "input": {
+                "body": [
+                    {"name": "type", "dtype": "string", "required": true},
+                    {"name": "learning-rate", "dtype": "float", "required": false},
+                    {"name": "2fast", "dtype": "int", "required": true}
+                ]
+            },
+            "output": [
+                {"name": "label", "dtype": "string", "required": true}
+            ]
+        });
+
+        let params = ServiceParams::from_value(&schema).expect("Failed to parse schema");
+        let module = generate_module("mnist", &params);
+
+        // Keywords are raw-escaped; serde strips the r# on the wire.
+        assert!(module.contains("pub r#type: String,"));
+        assert!(!module.contains("rename = \"type\""));
+
+        // Invalid characters and leading digits get renamed back.
+        assert!(module.contains("#[serde(rename = \"learning-rate\")]"));
+        assert!(module.contains("pub learning_rate: Option<f64>,"));
+        assert!(module.contains("#[serde(rename = \"2fast\")]"));
+        assert!(module.contains("pub _2fast: i64,"));
+    }
+}
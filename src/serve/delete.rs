@@ -1,15 +1,76 @@
-use crate::serve::get_server_url;
+use crate::serve::list::fetch_services;
+use crate::serve::{get_server_url, prompt_line, send_endpoint};
 use std::collections::HashMap;
 use utils::endpoints::{Endpoint, Method};
 use utils::prelude::*;
 
+// Deletes each requested version independently, reporting per-version
+// outcomes instead of aborting the whole batch at the first failure.
 #[tokio::main]
-pub async fn delete_service(
-    service_name: &str,
-    service_version: Option<u32>,
-) -> RResult<(), AnyErr2> {
+pub async fn delete_versions(service_name: &str, versions: &[u32]) -> RResult<(), AnyErr2> {
+    let mut failed = 0;
+
+    for &version in versions {
+        match delete_version(service_name, Some(version)).await {
+            Ok(_) => info!("Removed {} version {}", service_name, version),
+            Err(report) => {
+                failed += 1;
+                error!(
+                    "Failed to remove {} version {}: {:?}",
+                    service_name, version, report
+                );
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Report::new(err2!(format!(
+            "{} of {} version deletion(s) failed",
+            failed,
+            versions.len()
+        ))));
+    }
+
+    Ok(())
+}
+
+// Wiping every version is unrecoverable, so list what is about to go and
+// require the service name typed back before proceeding; --yes skips the
+// prompt for automation.
+#[tokio::main]
+pub async fn delete_all_versions(service_name: &str, yes: bool) -> RResult<(), AnyErr2> {
+    let response = fetch_services(Some(service_name), false).await?;
+    let services = response
+        .as_array()
+        .ok_or_else(|| err2!("Response is not an array"))?;
+
+    if services.is_empty() {
+        info!("No deployed versions of '{}' found", service_name);
+        return Ok(());
+    }
+
+    let versions: Vec<String> = services
+        .iter()
+        .map(|service| service["version"].as_i64().unwrap_or(0).to_string())
+        .collect();
+    println!(
+        "About to remove {} version(s) of '{}': {}",
+        versions.len(),
+        service_name,
+        versions.join(", ")
+    );
+
+    if !yes && prompt_line("Type the service name to confirm: ").as_deref() != Some(service_name) {
+        info!("Aborted - nothing deleted");
+        return Ok(());
+    }
+
+    delete_version(service_name, None).await
+}
+
+async fn delete_version(service_name: &str, service_version: Option<u32>) -> RResult<(), AnyErr2> {
     let mut endpoint_builder = Endpoint::builder()
-        .base_url(&get_server_url().await)
+        .base_url(&get_server_url().await?)
         .endpoint(&format!("/delete_service/{}", service_name))
         .method(Method::POST);
 
@@ -20,10 +81,14 @@ pub async fn delete_service(
     endpoint_builder = endpoint_builder.query_params(query);
     let endpoint = endpoint_builder.build().unwrap();
 
-    endpoint
-        .send()
-        .await
-        .change_context(err2!("Failed delete_service request"))?;
+    send_endpoint(
+        endpoint,
+        "POST",
+        &format!("/delete_service/{}", service_name),
+        None,
+        "Failed delete_service request",
+    )
+    .await?;
 
     Ok(())
 }
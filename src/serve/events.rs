@@ -0,0 +1,89 @@
+use crate::serve::{clear_screen, get_server_url, resolve_service_name, send_endpoint};
+use chrono::Utc;
+use comfy_table::modifiers::UTF8_ROUND_CORNERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Cell, CellAlignment, ContentArrangement, Table};
+use utils::endpoints::{Endpoint, Method};
+use utils::prelude::*;
+
+// Cluster events (scheduling failures, OOM kills, image pull errors) for
+// a service, the first place to look when a deploy won't start. --follow
+// re-polls on an interval like the other watch loops.
+#[tokio::main]
+pub async fn events_service(
+    service_name: Option<String>,
+    follow: bool,
+    interval: u64,
+) -> RResult<(), AnyErr2> {
+    let service_name = resolve_service_name(service_name).await?;
+
+    if !follow {
+        return render_events(&service_name).await;
+    }
+
+    loop {
+        clear_screen();
+
+        // Transient fetch errors shouldn't kill the follow loop.
+        if let Err(report) = render_events(&service_name).await {
+            warn!("Failed to refresh events: {:?}", report);
+        }
+
+        println!("Last refresh: {} (Ctrl-C to exit)", Utc::now().to_rfc3339());
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+    }
+}
+
+async fn render_events(service_name: &str) -> RResult<(), AnyErr2> {
+    let endpoint = Endpoint::builder()
+        .base_url(&get_server_url().await?)
+        .endpoint(&format!("/events/{}", service_name))
+        .method(Method::GET)
+        .build()
+        .unwrap();
+
+    let response = send_endpoint(
+        endpoint,
+        "GET",
+        &format!("/events/{}", service_name),
+        None,
+        "Failed to retrieve events",
+    )
+    .await?;
+
+    let events = response
+        .as_array()
+        .ok_or_else(|| err2!("Response is not an array"))?;
+
+    if events.is_empty() {
+        info!("No events recorded for '{}'", service_name);
+        return Ok(());
+    }
+
+    // Oldest first so the most recent event is closest to the prompt.
+    let mut events: Vec<&serde_json::Value> = events.iter().collect();
+    events.sort_by_key(|event| event["timestamp"].as_str().unwrap_or("").to_string());
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_width(180)
+        .set_header(vec!["Timestamp", "Type", "Reason", "Message"]);
+
+    for event in events {
+        table.add_row(vec![
+            Cell::new(event["timestamp"].as_str().unwrap_or("-"))
+                .set_alignment(CellAlignment::Center),
+            Cell::new(event["type"].as_str().unwrap_or("-")).set_alignment(CellAlignment::Center),
+            Cell::new(event["reason"].as_str().unwrap_or("-")),
+            Cell::new(event["message"].as_str().unwrap_or("-")),
+        ]);
+    }
+
+    println!("{table}");
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use crate::serve::{get_server_url, resolve_service_name, send_endpoint, ServiceParams};
+use utils::endpoints::{Endpoint, Method};
+use utils::prelude::*;
+
+// Fetches one deployed version's full spec - schema, resource request and
+// env vars - for diffing against the local schema.json. The summary table
+// from `serve ls` deliberately omits most of this.
+#[tokio::main]
+pub async fn get_service(service_name: Option<String>, version: u32) -> RResult<(), AnyErr2> {
+    let service_name = resolve_service_name(service_name).await?;
+
+    let path = format!("/get_service/{}/{}", service_name, version);
+    let endpoint = Endpoint::builder()
+        .base_url(&get_server_url().await?)
+        .endpoint(&path)
+        .method(Method::GET)
+        .build()
+        .unwrap();
+
+    let response = match send_endpoint(endpoint, "GET", &path, None, "Failed get_service request")
+        .await
+    {
+        Ok(response) => response,
+        Err(report) => {
+            if crate::serve::report_status(&report) == Some(reqwest::StatusCode::NOT_FOUND) {
+                return Err(Report::new(err2!(format!(
+                    "Service {} version {} not found - check `mlx serve ls {}` for deployed versions",
+                    service_name, version, service_name
+                ))));
+            }
+            return Err(report);
+        }
+    };
+
+    // Parse the schema through the same struct the deploy path uses, so a
+    // record this client can't round-trip fails loudly here.
+    if let Some(schema) = response.get("service_schema") {
+        ServiceParams::from_value(schema)
+            .change_context(err2!("Deployed service_schema failed validation"))?;
+    }
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&response)
+            .change_context(err2!("Failed to serialize the service record"))?
+    );
+
+    Ok(())
+}
@@ -1,33 +1,131 @@
-use crate::serve::get_server_url;
-use chrono::{DateTime, Utc};
+use crate::serve::{
+    clear_screen, elapsed_between, format_duration, format_timestamp, get_server_url,
+    send_endpoint, TzDisplay,
+};
+use chrono::Utc;
 use comfy_table::modifiers::UTF8_ROUND_CORNERS;
 use comfy_table::presets::UTF8_FULL;
-use comfy_table::{Cell, CellAlignment, ContentArrangement, Table};
+use comfy_table::{Cell, CellAlignment, Color, ContentArrangement, Table};
 use std::collections::HashMap;
 use utils::endpoints::{Endpoint, Method};
 use utils::prelude::*;
 
+// Honors the NO_COLOR convention and keeps piped output free of ANSI
+// escapes.
+fn colors_enabled() -> bool {
+    use std::io::IsTerminal;
+
+    std::env::var_os("NO_COLOR").is_none() && std::io::stdout().is_terminal()
+}
+
+// Job states the --status filter understands.
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum JobStatusFilter {
+    Started,
+    Ended,
+    Errored,
+}
+
 #[tokio::main]
-pub async fn jobs_service(service_name: &str) -> RResult<(), AnyErr2> {
+#[allow(clippy::too_many_arguments)]
+pub async fn jobs_service(
+    service_name: &str,
+    tz: TzDisplay,
+    page: Option<u32>,
+    page_size: Option<u32>,
+    watch: bool,
+    interval: u64,
+    status: Option<JobStatusFilter>,
+    limit: Option<usize>,
+    raw: bool,
+) -> RResult<(), AnyErr2> {
+    if !watch {
+        render_jobs(service_name, tz, page, page_size, status, limit, raw).await?;
+        return Ok(());
+    }
+
+    loop {
+        clear_screen();
+
+        // Transient fetch errors shouldn't kill the watch loop.
+        match render_jobs(service_name, tz, page, page_size, status, limit, raw).await {
+            Ok(any_started) => {
+                println!("Last refresh: {} (Ctrl-C to exit)", Utc::now().to_rfc3339());
+
+                if !any_started {
+                    info!("All jobs have ended - exiting watch");
+                    return Ok(());
+                }
+            }
+            Err(report) => warn!("Failed to refresh jobs: {:?}", report),
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+    }
+}
+
+// Renders one snapshot of the jobs table; returns whether any job is
+// still in the "started" state so --watch knows when to stop.
+async fn render_jobs(
+    service_name: &str,
+    tz: TzDisplay,
+    page: Option<u32>,
+    page_size: Option<u32>,
+    status_filter: Option<JobStatusFilter>,
+    limit: Option<usize>,
+    raw: bool,
+) -> RResult<bool, AnyErr2> {
     // Build the endpoint for fetching jobs
-    let endpoint = Endpoint::builder()
-        .base_url(&get_server_url().await)
+    let mut endpoint_builder = Endpoint::builder()
+        .base_url(&get_server_url().await?)
         .endpoint(&format!("/jobs/{}", service_name))
-        .method(Method::GET)
-        .build()
-        .unwrap();
+        .method(Method::GET);
+
+    // Pagination is server-side; older servers ignore these params and
+    // return the full map, which the parsing below still accepts.
+    let mut query = HashMap::new();
+    if let Some(page) = page {
+        query.insert("page".to_string(), page.to_string());
+    }
+    if let Some(page_size) = page_size {
+        query.insert("page_size".to_string(), page_size.to_string());
+    }
+    if !query.is_empty() {
+        endpoint_builder = endpoint_builder.query_params(query);
+    }
+
+    let endpoint = endpoint_builder.build().unwrap();
 
     // Send the request to the server
-    let response = endpoint
-        .send()
-        .await
-        .change_context(err2!("Failed to retrieve jobs"))?;
+    let response = send_endpoint(
+        endpoint,
+        "GET",
+        &format!("/jobs/{}", service_name),
+        None,
+        "Failed to retrieve jobs",
+    )
+    .await?;
+
+    debug!("Response: {:?}", response);
+
+    // Paginated servers wrap the map in {"jobs": ..., "page": X,
+    // "total_pages": Y}; everything else is the bare map.
+    let (jobs_value, page_footer) = match response.get("jobs") {
+        Some(jobs) => {
+            let footer = match (
+                response.get("page").and_then(|v| v.as_u64()),
+                response.get("total_pages").and_then(|v| v.as_u64()),
+            ) {
+                (Some(page), Some(total)) => Some(format!("Page {} of {}", page, total)),
+                _ => None,
+            };
+            (jobs.clone(), footer)
+        }
+        None => (response.clone(), None),
+    };
 
-    // Parse the response as a JSON object
-    error!("Response: {:?}", response);
     let logs: HashMap<String, HashMap<String, String>> =
-        serde_json::from_value(response.clone())
-            .change_context(err2!("Failed to parse response"))?;
+        serde_json::from_value(jobs_value).change_context(err2!("Failed to parse response"))?;
 
     // Prepare a table to display the job logs
     let mut table = Table::new();
@@ -38,44 +136,91 @@ pub async fn jobs_service(service_name: &str) -> RResult<(), AnyErr2> {
         .set_width(180)
         .set_header(vec!["Job ID", "Start Time", "Elapsed Time", "Status"]);
 
+    // HashMap iteration order is nondeterministic; newest-first makes the
+    // latest job the first row. Unparseable timestamps fall back to
+    // string ordering so they still land deterministically.
+    let mut jobs: Vec<(&String, &HashMap<String, String>)> = logs.iter().collect();
+    jobs.sort_by(|a, b| {
+        let a_raw = a.1.get("started_at").map(String::as_str).unwrap_or("");
+        let b_raw = b.1.get("started_at").map(String::as_str).unwrap_or("");
+
+        match (
+            chrono::DateTime::parse_from_rfc3339(a_raw),
+            chrono::DateTime::parse_from_rfc3339(b_raw),
+        ) {
+            (Ok(a_ts), Ok(b_ts)) => b_ts.cmp(&a_ts),
+            _ => b_raw.cmp(a_raw),
+        }
+    });
+
+    let mut any_started = false;
+    let mut shown = 0;
+
     // Iterate through each job log and populate the table
-    for (job_id, log) in logs.iter() {
+    for (job_id, log) in jobs {
         let start_time_str = log.get("started_at").unwrap_or(&"".to_string()).clone();
         let end_time_str = log.get("ended_at").unwrap_or(&"".to_string()).clone();
 
         // Parse start and end times to calculate elapsed time
-        let elapsed_time = if let Ok(start_time) = DateTime::parse_from_rfc3339(&start_time_str) {
-            let start_time = start_time.with_timezone(&Utc);
-            if !end_time_str.is_empty() {
-                if let Ok(end_time) = DateTime::parse_from_rfc3339(&end_time_str) {
-                    let duration = end_time.signed_duration_since(start_time);
-                    format!("{} ms", duration.num_milliseconds())
-                } else {
-                    "-".to_string()
-                }
-            } else {
-                "-".to_string()
-            }
-        } else {
-            "-".to_string()
+        let elapsed_time = match elapsed_between(&start_time_str, &end_time_str) {
+            Some(duration) if raw => format!("{} ms", duration.num_milliseconds()),
+            Some(duration) => format_duration(duration),
+            None => "-".to_string(),
         };
 
-        let status = if end_time_str.is_empty() {
+        // A failure reported by the server trumps the started/ended
+        // inference from the timestamps.
+        let errored = log.get("error").is_some_and(|error| !error.is_empty());
+        let status = if errored {
+            "errored"
+        } else if end_time_str.is_empty() {
+            any_started = true;
             "started"
         } else {
             "ended"
         }
         .to_string();
 
+        let keep = match status_filter {
+            Some(JobStatusFilter::Started) => status == "started",
+            Some(JobStatusFilter::Ended) => status == "ended",
+            Some(JobStatusFilter::Errored) => status == "errored",
+            None => true,
+        };
+        if !keep {
+            continue;
+        }
+
+        if let Some(limit) = limit {
+            if shown >= limit {
+                break;
+            }
+        }
+        shown += 1;
+
+        let mut status_cell = Cell::new(&status).set_alignment(CellAlignment::Center);
+        if colors_enabled() {
+            status_cell = status_cell.fg(match status.as_str() {
+                "started" => Color::Yellow,
+                "ended" => Color::Green,
+                "errored" => Color::Red,
+                _ => Color::Reset,
+            });
+        }
+
         table.add_row(vec![
             Cell::new(job_id).set_alignment(CellAlignment::Center),
-            Cell::new(start_time_str).set_alignment(CellAlignment::Center),
+            Cell::new(format_timestamp(&start_time_str, tz)).set_alignment(CellAlignment::Center),
             Cell::new(elapsed_time).set_alignment(CellAlignment::Center),
-            Cell::new(status).set_alignment(CellAlignment::Center),
+            status_cell,
         ]);
     }
     // Print the table
     println!("{table}");
 
-    Ok(())
+    if let Some(footer) = page_footer {
+        println!("{}", footer);
+    }
+
+    Ok(any_started)
 }
@@ -1,4 +1,4 @@
-use crate::serve::get_server_url;
+use crate::serve::{get_server_url, send_endpoint};
 use comfy_table::modifiers::UTF8_ROUND_CORNERS;
 use comfy_table::presets::UTF8_FULL;
 use comfy_table::{Cell, CellAlignment, ContentArrangement, Table};
@@ -7,10 +7,12 @@ use std::collections::HashMap;
 use utils::endpoints::{Endpoint, Method};
 use utils::prelude::*;
 
-#[tokio::main]
-pub async fn list_services(service_name: Option<&str>, pointers: bool) -> RResult<Value, AnyErr2> {
+pub(crate) async fn fetch_services(
+    service_name: Option<&str>,
+    pointers: bool,
+) -> RResult<Value, AnyErr2> {
     let mut endpoint_builder = Endpoint::builder()
-        .base_url(&get_server_url().await)
+        .base_url(&get_server_url().await?)
         .endpoint("/list_service")
         .method(Method::GET);
 
@@ -22,10 +24,33 @@ pub async fn list_services(service_name: Option<&str>, pointers: bool) -> RResul
     endpoint_builder = endpoint_builder.query_params(query);
     let endpoint = endpoint_builder.build().unwrap();
 
-    let response = endpoint
-        .send()
-        .await
-        .change_context(err2!("Failed list_service request"))?;
+    send_endpoint(
+        endpoint,
+        "GET",
+        "/list_service",
+        None,
+        "Failed list_service request",
+    )
+    .await
+}
+
+#[tokio::main]
+pub async fn list_services(
+    service_name: Option<&str>,
+    pointers: bool,
+    json: bool,
+) -> RResult<Value, AnyErr2> {
+    let response = fetch_services(service_name, pointers).await?;
+
+    // Machine-readable mode for piping into jq and friends.
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&response)
+                .change_context(err2!("Failed to serialize response"))?
+        );
+        return Ok(response);
+    }
 
     let services = response
         .as_array()
@@ -43,6 +68,7 @@ pub async fn list_services(service_name: Option<&str>, pointers: bool) -> RResul
             "CPU Limit",
             "Memory Limit",
             "Replicas",
+            "Concurrency",
             "Running",
             "Pod ID",
         ]);
@@ -61,6 +87,10 @@ pub async fn list_services(service_name: Option<&str>, pointers: bool) -> RResul
             .as_i64()
             .unwrap_or(0)
             .to_string();
+        let concurrency = service["resource_request"]["concurrent_jobs"]
+            .as_i64()
+            .map(|jobs| jobs.to_string())
+            .unwrap_or_else(|| "-".to_string());
         let running = service["running"].as_bool().unwrap_or(false).to_string();
 
         table.add_row(vec![
@@ -69,6 +99,7 @@ pub async fn list_services(service_name: Option<&str>, pointers: bool) -> RResul
             Cell::new(cpu_limit),
             Cell::new(memory_limit),
             Cell::new(replicas).set_alignment(CellAlignment::Center),
+            Cell::new(concurrency).set_alignment(CellAlignment::Center),
             Cell::new(running).set_alignment(CellAlignment::Center),
             Cell::new(pod_id),
         ]);
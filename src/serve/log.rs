@@ -1,14 +1,23 @@
-use crate::serve::get_server_url;
-use chrono::DateTime;
+use crate::serve::{
+    confirm_overwrite, elapsed_between, format_duration, format_timestamp, get_server_base_url,
+    get_server_url, send_endpoint, TzDisplay, HTTP_CLIENT,
+};
 use comfy_table::modifiers::UTF8_ROUND_CORNERS;
 use comfy_table::presets::UTF8_FULL;
 use comfy_table::{Cell, CellAlignment, ContentArrangement, Table};
+use futures_util::StreamExt;
 use serde_json::Value;
 use std::collections::HashMap;
 use utils::endpoints::{Endpoint, Method};
 use utils::prelude::*;
 
+// Payloads above this size are streamed line-by-line instead of being
+// buffered into a single JSON Value, keeping memory bounded for services
+// with multi-megabyte logs.
+static STREAM_THRESHOLD_BYTES: u64 = 1024 * 1024;
+
 #[tokio::main]
+#[allow(clippy::too_many_arguments)]
 pub async fn log_service(
     service_name: &str,
     job_id: &str,
@@ -16,9 +25,41 @@ pub async fn log_service(
     include_response: bool,
     include_logs: bool,
     include_timer: bool,
+    tz: TzDisplay,
+    export_json: Option<String>,
+    export: Option<String>,
+    reverse: bool,
+    raw: bool,
+    follow: bool,
 ) -> RResult<Value, AnyErr2> {
+    if follow {
+        follow_logs(service_name, job_id).await?;
+        return Ok(Value::Null);
+    }
+
+    let logs_url = format!(
+        "{}/logs/{}/{}",
+        get_server_base_url().await?,
+        service_name,
+        job_id
+    );
+
+    // Probe the payload size first; large log payloads get streamed rather
+    // than buffered. Servers that don't answer HEAD fall through to the
+    // buffered path below.
+    if let Ok(head) = HTTP_CLIENT.head(&logs_url).send().await {
+        if head
+            .content_length()
+            .is_some_and(|len| len > STREAM_THRESHOLD_BYTES)
+        {
+            info!("Log payload is large - streaming the raw JSON instead of rendering tables");
+            stream_log_payload(&logs_url).await?;
+            return Ok(Value::Null);
+        }
+    }
+
     let mut endpoint_builder = Endpoint::builder()
-        .base_url(&get_server_url().await)
+        .base_url(&get_server_url().await?)
         .endpoint(&format!("/logs/{}/{}", service_name, job_id))
         .method(Method::GET);
 
@@ -31,15 +72,23 @@ pub async fn log_service(
     endpoint_builder = endpoint_builder.query_params(query);
     let endpoint = endpoint_builder.build().unwrap();
 
-    let response = endpoint
-        .send()
-        .await
-        .change_context(err2!("Failed to retrieve logs"))?;
+    let response = send_endpoint(
+        endpoint,
+        "GET",
+        &format!("/logs/{}/{}", service_name, job_id),
+        None,
+        "Failed to retrieve logs",
+    )
+    .await?;
 
     let log_data: &serde_json::Map<String, Value> = response
         .as_object()
         .ok_or_else(|| err2!("Response is not an object"))?;
 
+    // With no section flags everything renders, matching the original
+    // behaviour; any explicit include flag switches to opt-in filtering.
+    let show_all = !(include_input || include_response || include_logs || include_timer);
+
     // Initialize the main table
     let mut main_table = Table::new();
     main_table
@@ -49,114 +98,377 @@ pub async fn log_service(
         .set_width(180);
 
     // Input section
-    // if include_input {
-    if let Some(validated_input) = log_data.get("validated_input") {
-        let mut input_table = Table::new();
-        input_table.set_header(vec![
-            Cell::new("User Input").add_attribute(comfy_table::Attribute::Bold)
-        ]);
+    if show_all || include_input {
+        if let Some(validated_input) = log_data.get("validated_input") {
+            let mut input_table = Table::new();
+            input_table.set_header(vec![
+                Cell::new("User Input").add_attribute(comfy_table::Attribute::Bold)
+            ]);
 
-        let pretty_input = match validated_input.as_str() {
-            Some(validated_str) => match serde_json::from_str::<Value>(validated_str) {
-                Ok(json_value) => serde_json::to_string_pretty(&json_value)
-                    .unwrap_or_else(|_| validated_str.to_string()),
-                Err(_) => validated_str.to_string(),
-            },
-            None => validated_input.to_string(),
-        };
+            let pretty_input = match validated_input.as_str() {
+                Some(validated_str) => match serde_json::from_str::<Value>(validated_str) {
+                    Ok(json_value) => serde_json::to_string_pretty(&json_value)
+                        .unwrap_or_else(|_| validated_str.to_string()),
+                    Err(_) => validated_str.to_string(),
+                },
+                None => validated_input.to_string(),
+            };
 
-        input_table.add_row(vec![Cell::new(pretty_input)]);
-        main_table.add_row(vec![Cell::new(input_table)]);
+            input_table.add_row(vec![Cell::new(pretty_input)]);
+            main_table.add_row(vec![Cell::new(input_table)]);
+        }
     }
-    // }
 
     // Response section
-    // if include_response {
-    if let Some(response) = log_data.get("response") {
-        let mut response_table = Table::new();
-        response_table.set_header(vec![
-            Cell::new("Service Response").add_attribute(comfy_table::Attribute::Bold)
-        ]);
+    if show_all || include_response {
+        if let Some(response) = log_data.get("response") {
+            let mut response_table = Table::new();
+            response_table.set_header(vec![
+                Cell::new("Service Response").add_attribute(comfy_table::Attribute::Bold)
+            ]);
 
-        let pretty_response = match response.as_str() {
-            Some(str) => match serde_json::from_str::<Value>(str) {
-                Ok(json_value) => {
-                    serde_json::to_string_pretty(&json_value).unwrap_or_else(|_| str.to_string())
-                }
-                Err(_) => str.to_string(),
-            },
-            None => response.to_string(),
-        };
+            let pretty_response = match response.as_str() {
+                Some(str) => match serde_json::from_str::<Value>(str) {
+                    Ok(json_value) => serde_json::to_string_pretty(&json_value)
+                        .unwrap_or_else(|_| str.to_string()),
+                    Err(_) => str.to_string(),
+                },
+                None => response.to_string(),
+            };
 
-        response_table.add_row(vec![Cell::new(pretty_response)]);
-        main_table.add_row(vec![Cell::new(response_table)]);
+            response_table.add_row(vec![Cell::new(pretty_response)]);
+            main_table.add_row(vec![Cell::new(response_table)]);
+        }
     }
-    // }
 
     // Timer section
-    // if include_timer {
-    let mut timer_table = Table::new();
-    timer_table.set_header(vec![
-        Cell::new("Timer").add_attribute(comfy_table::Attribute::Bold)
-    ]);
-
-    if let Some(started_at) = log_data.get("started_at") {
-        timer_table.add_row(vec![
-            Cell::new("Started At"),
-            Cell::new(started_at.to_string()).set_alignment(CellAlignment::Center),
+    if show_all || include_timer {
+        let mut timer_table = Table::new();
+        timer_table.set_header(vec![
+            Cell::new("Timer").add_attribute(comfy_table::Attribute::Bold)
         ]);
-    }
 
-    if let Some(ended_at) = log_data.get("ended_at") {
-        timer_table.add_row(vec![
-            Cell::new("Ended At"),
-            Cell::new(ended_at.to_string()).set_alignment(CellAlignment::Center),
-        ]);
-    }
+        if let Some(started_at) = log_data.get("started_at") {
+            let rendered = match started_at.as_str() {
+                Some(raw) => format_timestamp(raw, tz),
+                None => started_at.to_string(),
+            };
+            timer_table.add_row(vec![
+                Cell::new("Started At"),
+                Cell::new(rendered).set_alignment(CellAlignment::Center),
+            ]);
+        }
 
-    if let (Some(Value::String(started_at_str)), Some(Value::String(ended_at_str))) =
-        (log_data.get("started_at"), log_data.get("ended_at"))
-    {
-        let started_at = DateTime::parse_from_rfc3339(started_at_str)
-            .map_err(|_| err2!("Failed to parse started_at"))?;
-        let ended_at = DateTime::parse_from_rfc3339(ended_at_str)
-            .map_err(|_| err2!("Failed to parse ended_at"))?;
+        if let Some(ended_at) = log_data.get("ended_at") {
+            let rendered = match ended_at.as_str() {
+                Some(raw) => format_timestamp(raw, tz),
+                None => ended_at.to_string(),
+            };
+            timer_table.add_row(vec![
+                Cell::new("Ended At"),
+                Cell::new(rendered).set_alignment(CellAlignment::Center),
+            ]);
+        }
 
-        let duration = ended_at - started_at;
-        let elapsed_time = format!("{} ms", duration.num_milliseconds());
+        if let (Some(Value::String(started_at_str)), Some(Value::String(ended_at_str))) =
+            (log_data.get("started_at"), log_data.get("ended_at"))
+        {
+            let elapsed_time = match elapsed_between(started_at_str, ended_at_str) {
+                Some(duration) if raw => format!("{} ms", duration.num_milliseconds()),
+                Some(duration) => format_duration(duration),
+                None => "-".to_string(),
+            };
 
-        timer_table.add_row(vec![
-            Cell::new("Elapsed Time"),
-            Cell::new(elapsed_time).set_alignment(CellAlignment::Center),
-        ]);
+            timer_table.add_row(vec![
+                Cell::new("Elapsed Time"),
+                Cell::new(elapsed_time).set_alignment(CellAlignment::Center),
+            ]);
+        }
+
+        main_table.add_row(vec![Cell::new(timer_table)]);
     }
 
-    main_table.add_row(vec![Cell::new(timer_table)]);
+    // Logs section. Chronological (oldest-first) by default; --reverse
+    // flips to newest-first for tailing long logs.
+    if show_all || include_logs {
+        if let Some(logs) = log_data.get("logs") {
+            let logs_table = match logs.as_array() {
+                // Structured entries render timestamp and message as
+                // separate columns.
+                Some(entries) => {
+                    let mut table = Table::new();
+                    table.set_header(vec![
+                        Cell::new("Timestamp").add_attribute(comfy_table::Attribute::Bold),
+                        Cell::new("Message").add_attribute(comfy_table::Attribute::Bold),
+                    ]);
 
-    // Logs section
-    // if include_logs {
-    if let Some(logs) = log_data.get("logs") {
-        let mut logs_table = Table::new();
-        logs_table.set_header(vec![
-            Cell::new("Logs").add_attribute(comfy_table::Attribute::Bold)
-        ]);
+                    let mut rows: Vec<(String, String)> = entries
+                        .iter()
+                        .map(|entry| {
+                            let timestamp = entry
+                                .get("timestamp")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or("-")
+                                .to_string();
+                            let message = entry
+                                .get("message")
+                                .and_then(|v| v.as_str())
+                                .map(str::to_string)
+                                .unwrap_or_else(|| entry.to_string());
+                            (timestamp, message)
+                        })
+                        .collect();
+                    if reverse {
+                        rows.reverse();
+                    }
 
-        // Convert the log string to lines, reverse them, and add each line as a separate row
-        let log_entries: Vec<&str> = logs.as_str().unwrap_or("").lines().collect();
-        for entry in log_entries {
-            logs_table.add_row(vec![Cell::new(entry).set_alignment(CellAlignment::Left)]);
-        }
+                    for (timestamp, message) in rows {
+                        table.add_row(vec![
+                            Cell::new(timestamp),
+                            Cell::new(message).set_alignment(CellAlignment::Left),
+                        ]);
+                    }
+                    table
+                }
+                // A plain newline-delimited string renders one line per row.
+                None => {
+                    let mut table = Table::new();
+                    table.set_header(vec![
+                        Cell::new("Logs").add_attribute(comfy_table::Attribute::Bold)
+                    ]);
 
-        main_table.add_row(vec![
-            Cell::new(logs_table).set_alignment(CellAlignment::Left)
-        ]);
+                    let mut log_entries: Vec<&str> = logs.as_str().unwrap_or("").lines().collect();
+                    if reverse {
+                        log_entries.reverse();
+                    }
+
+                    for entry in log_entries {
+                        table.add_row(vec![Cell::new(entry).set_alignment(CellAlignment::Left)]);
+                    }
+                    table
+                }
+            };
+
+            main_table.add_row(vec![
+                Cell::new(logs_table).set_alignment(CellAlignment::Left)
+            ]);
+        }
     }
-    // }
 
     debug!("Main Table: {:?}", "FOO");
 
     // Output the main table
     println!("{main_table}");
 
+    if let Some(path) = export_json {
+        export_structured_json(log_data, &path)?;
+    }
+
+    if let Some(path) = export {
+        export_payload(log_data, &path)?;
+    }
+
     Ok(response)
 }
+
+// Unwraps a JSON-encoded string into the object it contains, mirroring
+// the pretty-printing above; anything else passes through untouched.
+fn unwrap_json_string(value: &Value) -> Value {
+    match value.as_str() {
+        Some(raw) => serde_json::from_str(raw).unwrap_or_else(|_| value.clone()),
+        None => value.clone(),
+    }
+}
+
+// Flattens the logs field into plain lines whichever shape the server
+// sent: structured entries contribute their message, a plain string is
+// split on newlines. Shared by the exports so neither goes empty on the
+// structured shape.
+fn log_lines(logs: &Value) -> Vec<String> {
+    match logs.as_array() {
+        Some(entries) => entries
+            .iter()
+            .map(|entry| {
+                entry
+                    .get("message")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| entry.to_string())
+            })
+            .collect(),
+        None => logs
+            .as_str()
+            .unwrap_or("")
+            .lines()
+            .map(str::to_string)
+            .collect(),
+    }
+}
+
+// Writes the log sections as one structured JSON document, a clean
+// artifact for incident reports. Parent directories are created and an
+// existing file is only replaced after confirmation.
+fn export_structured_json(
+    log_data: &serde_json::Map<String, Value>,
+    path: &str,
+) -> RResult<(), AnyErr2> {
+    let elapsed_ms = match (
+        log_data.get("started_at").and_then(|v| v.as_str()),
+        log_data.get("ended_at").and_then(|v| v.as_str()),
+    ) {
+        (Some(started_at), Some(ended_at)) => {
+            elapsed_between(started_at, ended_at).map(|d| d.num_milliseconds())
+        }
+        _ => None,
+    };
+
+    let record = serde_json::json!({
+        "validated_input": log_data.get("validated_input").map(unwrap_json_string),
+        "response": log_data.get("response").map(unwrap_json_string),
+        "timer": {
+            "started_at": log_data.get("started_at"),
+            "ended_at": log_data.get("ended_at"),
+            "elapsed_ms": elapsed_ms,
+        },
+        "logs": log_data.get("logs").map(log_lines),
+    });
+
+    let target = std::path::Path::new(path);
+    if let Some(parent) = target.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)
+                .change_context(err2!(format!("Failed to create {}", parent.display())))?;
+        }
+    }
+
+    if target.exists() && !confirm_overwrite(path) {
+        info!("Aborted - {} left untouched", path);
+        return Ok(());
+    }
+
+    let pretty =
+        serde_json::to_string_pretty(&record).change_context(err2!("Failed to render export"))?;
+    std::fs::write(target, pretty + "\n")
+        .change_context(err2!(format!("Failed to write {}", path)))?;
+
+    info!("Exported structured logs to {}", path);
+
+    Ok(())
+}
+
+// Writes the raw payload to disk: a .json path gets the whole response
+// pretty-printed, a .txt path just the concatenated log lines. Anything
+// else errors so a typo doesn't silently pick a format.
+fn export_payload(log_data: &serde_json::Map<String, Value>, path: &str) -> RResult<(), AnyErr2> {
+    let contents = if path.ends_with(".json") {
+        let pretty = serde_json::to_string_pretty(&Value::Object(log_data.clone()))
+            .change_context(err2!("Failed to render export"))?;
+        pretty + "\n"
+    } else if path.ends_with(".txt") {
+        let mut lines = log_data
+            .get("logs")
+            .map(|logs| log_lines(logs).join("\n"))
+            .unwrap_or_default();
+        if !lines.ends_with('\n') {
+            lines.push('\n');
+        }
+        lines
+    } else {
+        return Err(Report::new(err2!(
+            "--export expects a path ending in .json or .txt"
+        )));
+    };
+
+    let target = std::path::Path::new(path);
+    if let Some(parent) = target.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)
+                .change_context(err2!(format!("Failed to create {}", parent.display())))?;
+        }
+    }
+
+    if target.exists() && !confirm_overwrite(path) {
+        info!("Aborted - {} left untouched", path);
+        return Ok(());
+    }
+
+    std::fs::write(target, &contents).change_context(err2!(format!("Failed to write {}", path)))?;
+
+    info!("Exported {} bytes to {}", contents.len(), path);
+
+    Ok(())
+}
+
+// Poll interval for --follow, mirroring `kubectl logs -f`.
+static FOLLOW_POLL_INTERVAL_SECS: u64 = 2;
+
+// Re-fetches the job's logs on an interval, printing only lines that are
+// new since the previous poll. Exits when the job reports an end time;
+// Ctrl-C stops it at any point. Also chained from `serve run --logs`.
+pub(crate) async fn follow_logs(service_name: &str, job_id: &str) -> RResult<(), AnyErr2> {
+    let path = format!("/logs/{}/{}", service_name, job_id);
+    let mut printed_lines = 0;
+
+    loop {
+        let endpoint = Endpoint::builder()
+            .base_url(&get_server_url().await?)
+            .endpoint(&path)
+            .method(Method::GET)
+            .build()
+            .unwrap();
+
+        let response =
+            send_endpoint(endpoint, "GET", &path, None, "Failed to retrieve logs").await?;
+
+        let log_data = response
+            .as_object()
+            .ok_or_else(|| err2!("Response is not an object"))?;
+
+        if let Some(logs) = log_data.get("logs").and_then(|v| v.as_str()) {
+            let lines: Vec<&str> = logs.lines().collect();
+            for line in lines.iter().skip(printed_lines) {
+                println!("{}", line);
+            }
+            printed_lines = lines.len();
+        }
+
+        let ended = log_data
+            .get("ended_at")
+            .and_then(|v| v.as_str())
+            .is_some_and(|ended_at| !ended_at.is_empty());
+        if ended {
+            info!("Job {} ended", job_id);
+            return Ok(());
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(FOLLOW_POLL_INTERVAL_SECS)).await;
+    }
+}
+
+// Relays the response body to stdout chunk by chunk as it arrives, so
+// the whole payload is never held in memory at once. The server sends
+// one compact JSON document with no newlines, so the chunks are written
+// verbatim rather than split into lines.
+async fn stream_log_payload(url: &str) -> RResult<(), AnyErr2> {
+    use std::io::Write;
+
+    let response = HTTP_CLIENT
+        .get(url)
+        .send()
+        .await
+        .change_context(err2!("Failed to retrieve logs"))?;
+
+    let mut stream = response.bytes_stream();
+    let mut stdout = std::io::stdout().lock();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.change_context(err2!("Failed to read log stream chunk"))?;
+        stdout
+            .write_all(&chunk)
+            .change_context(err2!("Failed to write the log stream"))?;
+    }
+
+    writeln!(stdout).change_context(err2!("Failed to write the log stream"))?;
+
+    Ok(())
+}
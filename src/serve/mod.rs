@@ -1,58 +1,654 @@
+pub mod call;
+pub mod codegen;
 pub mod create;
 pub mod delete;
+pub mod events;
+pub mod get;
 pub mod jobs;
 pub mod list;
 pub mod log;
 pub mod run;
 pub mod scale;
+pub mod schema;
+pub mod status;
+pub mod top;
+pub mod validate;
 
 // re-exports crud functions
+pub use call::*;
+pub use codegen::*;
 pub use create::*;
 pub use delete::*;
+pub use events::*;
+pub use get::*;
 pub use jobs::*;
 pub use list::*;
 pub use log::*;
 pub use run::*;
 pub use scale::*;
+pub use schema::*;
+pub use status::*;
+pub use top::*;
+pub use validate::*;
 
 // use lazy_static::lazy_static;
 use once_cell::sync::Lazy;
-use reqwest::get;
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::OnceCell;
+use utils::endpoints::Endpoint;
+use utils::prelude::*;
 
 static LOCAL_SERVER_URL: &str = "http://localhost:3000/test";
 static REMOTE_SERVER_URL: &str = "http://3.132.162.86:30000/test";
 
 static SERVER_URL: Lazy<OnceCell<Arc<String>>> = Lazy::new(|| OnceCell::new());
 
-async fn lazy_load_server_url() -> Arc<String> {
+// Explicit configuration wins over probing: the MLX_SERVER_URL env var
+// first, then the server_url key in the config file.
+fn resolve_configured_server_url() -> Option<String> {
+    if let Ok(url) = std::env::var("MLX_SERVER_URL") {
+        if !url.is_empty() {
+            return Some(url);
+        }
+    }
+
+    crate::config::CLIENT_CONFIG.server_url.clone()
+}
+
+async fn lazy_load_server_url() -> RResult<Arc<String>, AnyErr2> {
+    // A configured URL is still probed, but there is no fallback: pointing
+    // at a staging cluster and silently landing on prod would be worse
+    // than an error.
+    if let Some(url) = resolve_configured_server_url() {
+        if is_server_available(&url).await {
+            return Ok(Arc::new(url));
+        }
+        return Err(Report::new(err2!(format!(
+            "Configured server {} is not reachable - check MLX_SERVER_URL or your config file",
+            url
+        ))));
+    }
+
     // Try connecting to the local server first
     if is_server_available(LOCAL_SERVER_URL).await {
-        println!("Connected to local server: {}", LOCAL_SERVER_URL);
-        return Arc::new(LOCAL_SERVER_URL.to_string());
+        return Ok(Arc::new(LOCAL_SERVER_URL.to_string()));
     }
 
     // Try connecting to the remote server if the local one is unavailable
     if is_server_available(REMOTE_SERVER_URL).await {
-        println!("Connected to remote server: {}", REMOTE_SERVER_URL);
-        return Arc::new(REMOTE_SERVER_URL.to_string());
+        return Ok(Arc::new(REMOTE_SERVER_URL.to_string()));
     }
 
-    // Panic if neither server is reachable
-    panic!("No server available: could not connect to either local or remote server");
+    Err(Report::new(err2!(
+        "Could not connect to any MLX server - is one running locally, or pass --server / MLX_SERVER_URL"
+    )))
+}
+
+fn server_profile(url: &str) -> &'static str {
+    if url == LOCAL_SERVER_URL {
+        "local"
+    } else {
+        "remote"
+    }
+}
+
+// How long a probe waits before the candidate is considered down. A
+// server that accepts TCP but never answers would otherwise hang the
+// local-vs-remote fallback forever.
+static PROBE_TIMEOUT_ENV: &str = "MLX_PROBE_TIMEOUT_SECS";
+static DEFAULT_PROBE_TIMEOUT_SECS: u64 = 3;
+
+fn probe_timeout() -> std::time::Duration {
+    let secs = std::env::var(PROBE_TIMEOUT_ENV)
+        .ok()
+        .and_then(|raw| raw.parse().ok())
+        .filter(|&secs| secs >= 1)
+        .unwrap_or(DEFAULT_PROBE_TIMEOUT_SECS);
+
+    std::time::Duration::from_secs(secs)
 }
 
 async fn is_server_available(url: &str) -> bool {
-    match get(url).await {
+    let client = match reqwest::Client::builder()
+        .connect_timeout(probe_timeout())
+        .timeout(probe_timeout())
+        .build()
+    {
+        Ok(client) => client,
+        Err(_) => return false,
+    };
+
+    match client.get(url).send().await {
         Ok(response) => response.status().is_success(),
         Err(_) => false,
     }
 }
 
-async fn get_server_url() -> Arc<String> {
+// Seeds the server URL from the global --server flag before any command
+// runs, bypassing env/config/probing entirely. Errors if resolution
+// already happened, since a late override would silently be ignored.
+pub fn set_server_url(url: &str) -> RResult<(), AnyErr2> {
     SERVER_URL
-        .get_or_init(|| async { lazy_load_server_url().await })
-        .await
-        .clone()
+        .set(Arc::new(url.trim_end_matches('/').to_string()))
+        .map_err(|_| {
+            Report::new(err2!(
+                "Server URL was already resolved - --server must be applied before any request"
+            ))
+        })
+}
+
+pub(crate) async fn get_server_url() -> RResult<Arc<String>, AnyErr2> {
+    let url = SERVER_URL
+        .get_or_try_init(|| async { lazy_load_server_url().await })
+        .await?
+        .clone();
+
+    // Banner showing which cluster this command targets, printed once per
+    // invocation so destructive commands like rm make the target obvious.
+    // Goes to stderr so machine-readable stdout (--json, --output json)
+    // stays clean. Suppressed under the global --quiet flag.
+    static BANNER: std::sync::Once = std::sync::Once::new();
+    BANNER.call_once(|| {
+        if !QUIET.load(Ordering::Relaxed) {
+            eprintln!("Server: {} ({})", url, server_profile(&url));
+        }
+    });
+
+    Ok(url)
+}
+
+// The built-in probe URLs end in the /test health path; requests built
+// with format! against the server root (handle_request, raw log
+// streaming) must not inherit it, matching what the old standalone
+// CALL_SERVICE_URL pointed at. Endpoint-built requests keep the resolved
+// URL unchanged.
+pub(crate) async fn get_server_base_url() -> RResult<String, AnyErr2> {
+    let url = get_server_url().await?;
+    Ok(url
+        .trim_end_matches('/')
+        .trim_end_matches("/test")
+        .trim_end_matches('/')
+        .to_string())
+}
+
+// Shared reqwest client for the direct HTTP calls this module makes.
+// Built once with gzip/deflate enabled so compressed server responses are
+// transparently decoded before any JSON parsing.
+// Pool tuning comes from the optional config file so watch modes and
+// parallel deploys can keep connections warm.
+pub(crate) static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    let config = &*crate::config::CLIENT_CONFIG;
+
+    reqwest::Client::builder()
+        .gzip(true)
+        .deflate(true)
+        .pool_idle_timeout(std::time::Duration::from_secs(
+            config.pool_idle_timeout_secs,
+        ))
+        .pool_max_idle_per_host(config.pool_max_idle_per_host)
+        .build()
+        .expect("Failed to build HTTP client")
+});
+
+static TRACE_HTTP: AtomicBool = AtomicBool::new(false);
+static NO_INTERACTIVE: AtomicBool = AtomicBool::new(false);
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+// Toggled once at startup from the global --trace-http flag.
+pub fn set_trace_http(enabled: bool) {
+    TRACE_HTTP.store(enabled, Ordering::Relaxed);
+}
+
+// Toggled once at startup from the global --no-interactive flag so
+// scripts never block on a selection prompt.
+pub fn set_no_interactive(enabled: bool) {
+    NO_INTERACTIVE.store(enabled, Ordering::Relaxed);
+}
+
+// Toggled once at startup from the global --quiet flag.
+pub fn set_quiet(enabled: bool) {
+    QUIET.store(enabled, Ordering::Relaxed);
+}
+
+fn trace_http_enabled() -> bool {
+    TRACE_HTTP.load(Ordering::Relaxed)
+}
+
+pub(crate) fn quiet_enabled() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+// Attempts per send, overridable for flaky links or CI via env.
+static SEND_RETRIES_ENV: &str = "MLX_SEND_RETRIES";
+static DEFAULT_SEND_RETRIES: u32 = 3;
+
+fn send_retries() -> u32 {
+    std::env::var(SEND_RETRIES_ENV)
+        .ok()
+        .and_then(|raw| raw.parse().ok())
+        .filter(|&n| n >= 1)
+        .unwrap_or(DEFAULT_SEND_RETRIES)
+}
+
+// Pulls the HTTP status out of a report's frames, if any request in the
+// chain got far enough to receive one. Reading it structurally is the
+// only safe way to classify a failure - grepping the rendered report
+// would misfire on ports and ids that merely contain "404".
+pub(crate) fn report_status(report: &Report<AnyErr2>) -> Option<reqwest::StatusCode> {
+    report
+        .frames()
+        .filter_map(|frame| frame.downcast_ref::<reqwest::Error>())
+        .find_map(|error| error.status())
+}
+
+// Retry only what can plausibly succeed on a second attempt: connection
+// errors, timeouts and 5xx. A report with no status anywhere is treated
+// as a transport failure and retried.
+fn is_non_retryable(report: &Report<AnyErr2>) -> bool {
+    report_status(report).is_some_and(|status| status.is_client_error())
+}
+
+// Single choke point for Endpoint sends so --trace-http logs every
+// request/response pair, transient failures are retried with backoff,
+// and failures carry a consistent error context.
+pub(crate) async fn send_endpoint(
+    endpoint: Endpoint,
+    method: &str,
+    path: &str,
+    body: Option<&Value>,
+    failure_msg: &str,
+) -> RResult<Value, AnyErr2> {
+    if trace_http_enabled() {
+        match body {
+            Some(body) => info!("HTTP {} {} body: {}", method, path, redact_secrets(body)),
+            None => info!("HTTP {} {}", method, path),
+        }
+    }
+
+    let retries = send_retries();
+    let mut attempt = 0;
+    let response = loop {
+        match endpoint.send().await {
+            Ok(value) => break Ok(value),
+            Err(report) => {
+                attempt += 1;
+                if attempt >= retries || is_non_retryable(&report) {
+                    break Err(report);
+                }
+
+                // 1s, 2s, 4s... between attempts.
+                let delay = 2u64.pow(attempt - 1);
+                warn!(
+                    "HTTP {} {} failed (attempt {}/{}), retrying in {}s",
+                    method, path, attempt, retries, delay
+                );
+                tokio::time::sleep(tokio::time::Duration::from_secs(delay)).await;
+            }
+        }
+    };
+    let response = response.change_context(err2!(failure_msg.to_string()));
+
+    if trace_http_enabled() {
+        match &response {
+            Ok(value) => info!(
+                "HTTP {} {} response: {}",
+                method,
+                path,
+                redact_secrets(value)
+            ),
+            Err(report) => info!("HTTP {} {} failed: {:?}", method, path, report),
+        }
+    }
+
+    response
+}
+
+// Masks values under obviously sensitive keys before they hit the logs.
+fn redact_secrets(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, val)| {
+                    let lower = key.to_lowercase();
+                    if lower.contains("password")
+                        || lower.contains("token")
+                        || lower.contains("secret")
+                    {
+                        (key.clone(), Value::String("***".to_string()))
+                    } else {
+                        (key.clone(), redact_secrets(val))
+                    }
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(redact_secrets).collect()),
+        other => other.clone(),
+    }
+}
+
+// True when prompting is impossible: --no-interactive was passed or stdin
+// isn't a TTY. Every prompt site checks this so scripts fail fast instead
+// of hanging on a read that can never be answered.
+pub(crate) fn prompts_disabled() -> bool {
+    NO_INTERACTIVE.load(Ordering::Relaxed) || !std::io::IsTerminal::is_terminal(&std::io::stdin())
+}
+
+// Shared line prompt honoring --no-interactive; None when prompting is
+// impossible or stdin errored, which callers treat as a declined answer.
+pub(crate) fn prompt_line(prompt: &str) -> Option<String> {
+    if prompts_disabled() {
+        warn!("Input required but prompts are disabled (--no-interactive or no TTY) - aborting");
+        return None;
+    }
+
+    print!("{}", prompt);
+    let _ = std::io::Write::flush(&mut std::io::stdout());
+
+    let mut input = String::new();
+    if std::io::stdin().read_line(&mut input).is_err() {
+        return None;
+    }
+    Some(input.trim().to_string())
+}
+
+// Shared y/N prompt; anything but an explicit yes declines.
+pub(crate) fn confirm(prompt: &str) -> bool {
+    match prompt_line(prompt) {
+        Some(input) => matches!(input.to_lowercase().as_str(), "y" | "yes"),
+        None => false,
+    }
+}
+
+// Shared y/N prompt used before overwriting user files.
+pub(crate) fn confirm_overwrite(target: &str) -> bool {
+    confirm(&format!("{} already exists - overwrite? [y/N]: ", target))
+}
+
+// ANSI clear + cursor home, shared by the --watch render loops.
+pub(crate) fn clear_screen() {
+    print!("\x1B[2J\x1B[H");
+    let _ = std::io::Write::flush(&mut std::io::stdout());
+}
+
+// Resolves the service name for commands that can default to the local
+// mlx.toml when run inside a service directory, falling back to an
+// interactive picker over the deployed services. An explicit argument
+// always takes precedence.
+pub async fn resolve_service_name(explicit: Option<String>) -> RResult<String, AnyErr2> {
+    if let Some(name) = explicit {
+        return Ok(name);
+    }
+
+    if let Ok(toml_data) = std::fs::read_to_string(crate::SERVICE_TOML_PATH) {
+        let conf: toml::Value =
+            toml::from_str(&toml_data).change_context(err2!("Failed to parse mlx.toml"))?;
+
+        if let Some(name) = conf.get("service").and_then(|v| v.as_str()) {
+            info!("Defaulting to service '{}' from mlx.toml", name);
+            return Ok(name.to_string());
+        }
+    }
+
+    pick_service_interactively().await
+}
+
+// Numbered stdin picker over the deployed services, used when a command
+// needs a service name, none was given, and there's no local mlx.toml.
+// Disabled in non-TTY contexts and under --no-interactive.
+async fn pick_service_interactively() -> RResult<String, AnyErr2> {
+    if prompts_disabled() {
+        return Err(Report::new(err2!(
+            "No service name given - pass one explicitly or run inside a service directory"
+        )));
+    }
+
+    let response = list::fetch_services(None, true).await?;
+    let services = response
+        .as_array()
+        .ok_or_else(|| err2!("Response is not an array"))?;
+
+    let mut names: Vec<String> = services
+        .iter()
+        .filter_map(|service| service["name"].as_str().map(str::to_string))
+        .collect();
+    names.sort();
+    names.dedup();
+
+    if names.is_empty() {
+        return Err(Report::new(err2!("No services are deployed")));
+    }
+
+    println!("Select a service:");
+    for (idx, name) in names.iter().enumerate() {
+        println!("  {}) {}", idx + 1, name);
+    }
+    print!("Enter a number: ");
+    let _ = std::io::Write::flush(&mut std::io::stdout());
+
+    let mut input = String::new();
+    std::io::stdin()
+        .read_line(&mut input)
+        .change_context(err2!("Failed to read selection"))?;
+
+    let choice: usize = input
+        .trim()
+        .parse()
+        .change_context(err2!("Selection was not a number"))?;
+
+    names
+        .get(choice.wrapping_sub(1))
+        .cloned()
+        .ok_or_else(|| Report::new(err2!(format!("Selection {} is out of range", choice))))
+}
+
+// Display zone for rendered timestamps, selectable via --tz on the
+// commands that print them.
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum TzDisplay {
+    Local,
+    Utc,
+}
+
+// Shared timestamp rendering so every command displays started_at/ended_at
+// in the same zone and format. Unparseable values pass through untouched.
+pub(crate) fn format_timestamp(raw: &str, tz: TzDisplay) -> String {
+    match chrono::DateTime::parse_from_rfc3339(raw) {
+        Ok(ts) => match tz {
+            TzDisplay::Local => ts
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            TzDisplay::Utc => ts
+                .with_timezone(&chrono::Utc)
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string(),
+        },
+        Err(_) => raw.to_string(),
+    }
+}
+
+// Human-friendly duration rendering shared by the log timer table and the
+// jobs elapsed column: "450ms", "12s", "1m 23s", "2h 5m" depending on
+// magnitude. --raw keeps the millisecond value for anyone parsing output.
+pub(crate) fn format_duration(duration: chrono::Duration) -> String {
+    let ms = duration.num_milliseconds();
+    if ms < 1_000 {
+        return format!("{}ms", ms);
+    }
+
+    let secs = ms / 1_000;
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3_600 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {}m", secs / 3_600, (secs % 3_600) / 60)
+    }
+}
+
+// Shared RFC3339 elapsed-time calculation so log_service and jobs_service
+// render durations identically. Returns None when either timestamp is
+// missing or unparseable, which callers render as "-".
+pub(crate) fn elapsed_between(started_at: &str, ended_at: &str) -> Option<chrono::Duration> {
+    if started_at.is_empty() || ended_at.is_empty() {
+        return None;
+    }
+
+    let started_at = chrono::DateTime::parse_from_rfc3339(started_at).ok()?;
+    let ended_at = chrono::DateTime::parse_from_rfc3339(ended_at).ok()?;
+
+    Some(ended_at.signed_duration_since(started_at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_var_overrides_server_resolution() {
+        std::env::set_var("MLX_SERVER_URL", "http://staging.example.com:3000");
+        let resolved = resolve_configured_server_url();
+        std::env::remove_var("MLX_SERVER_URL");
+
+        assert_eq!(resolved.as_deref(), Some("http://staging.example.com:3000"));
+    }
+
+    #[test]
+    fn test_elapsed_between_valid_timestamps() {
+        let elapsed = elapsed_between("2024-01-01T00:00:00Z", "2024-01-01T00:00:01.500Z")
+            .expect("Expected a duration");
+        assert_eq!(elapsed.num_milliseconds(), 1500);
+    }
+
+    #[test]
+    fn test_elapsed_between_mixed_offsets() {
+        let elapsed = elapsed_between("2024-01-01T00:00:00+01:00", "2024-01-01T00:00:00Z")
+            .expect("Expected a duration");
+        assert_eq!(elapsed.num_milliseconds(), 3_600_000);
+    }
+
+    #[test]
+    fn test_elapsed_between_missing_or_invalid() {
+        assert!(elapsed_between("", "2024-01-01T00:00:00Z").is_none());
+        assert!(elapsed_between("2024-01-01T00:00:00Z", "").is_none());
+        assert!(elapsed_between("not-a-timestamp", "2024-01-01T00:00:00Z").is_none());
+    }
+
+    #[test]
+    fn test_format_duration_magnitudes() {
+        assert_eq!(
+            format_duration(chrono::Duration::milliseconds(450)),
+            "450ms"
+        );
+        assert_eq!(format_duration(chrono::Duration::seconds(12)), "12s");
+        assert_eq!(format_duration(chrono::Duration::seconds(83)), "1m 23s");
+        assert_eq!(
+            format_duration(chrono::Duration::seconds(2 * 3600 + 5 * 60)),
+            "2h 5m"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_probe_gives_up_on_unresponsive_server() {
+        // A listener that accepts connections but never answers - the
+        // probe should time out and report the server as unavailable.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind listener");
+        let addr = listener.local_addr().expect("Failed to read local addr");
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((socket, _)) = listener.accept().await else {
+                    break;
+                };
+                // Hold the socket open without ever writing a response.
+                tokio::spawn(async move {
+                    let _socket = socket;
+                    tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+                });
+            }
+        });
+
+        std::env::set_var(PROBE_TIMEOUT_ENV, "1");
+        let available = is_server_available(&format!("http://{}", addr)).await;
+        std::env::remove_var(PROBE_TIMEOUT_ENV);
+
+        assert!(
+            !available,
+            "Expected the probe to give up on a silent server"
+        );
+    }
+
+    // Bitwise CRC-32, enough for the handful of test bytes below.
+    fn crc32(data: &[u8]) -> u32 {
+        let mut crc = 0xffff_ffffu32;
+        for &byte in data {
+            crc ^= byte as u32;
+            for _ in 0..8 {
+                let mask = (crc & 1).wrapping_neg();
+                crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+            }
+        }
+        !crc
+    }
+
+    // Wraps the payload in a minimal gzip container around a single
+    // stored (uncompressed) deflate block - a valid gzip stream without
+    // pulling a compression crate into the test deps.
+    fn gzip_stored(data: &[u8]) -> Vec<u8> {
+        let mut out = vec![0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff];
+        let len = data.len() as u16;
+        out.push(0x01); // BFINAL=1, BTYPE=00 (stored)
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(data);
+        out.extend_from_slice(&crc32(data).to_le_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out
+    }
+
+    #[tokio::test]
+    async fn test_http_client_decodes_gzipped_json() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let gzipped = gzip_stored(br#"{"status":"ok"}"#);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind listener");
+        let addr = listener.local_addr().expect("Failed to read local addr");
+
+        tokio::spawn(async move {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+
+            let headers = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Encoding: gzip\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n",
+                gzipped.len()
+            );
+            let _ = socket.write_all(headers.as_bytes()).await;
+            let _ = socket.write_all(&gzipped).await;
+            let _ = socket.shutdown().await;
+        });
+
+        let response = HTTP_CLIENT
+            .get(format!("http://{}/", addr))
+            .send()
+            .await
+            .expect("Request failed");
+        let json: serde_json::Value = response
+            .json()
+            .await
+            .expect("Expected the gzipped body to decode into JSON");
+
+        assert_eq!(json["status"], "ok");
+    }
 }
@@ -1,16 +1,22 @@
-use crate::serve::get_server_url;
+use crate::serve::list::fetch_services;
+use crate::serve::{get_server_url, resolve_service_name, send_endpoint};
 use clap::Args;
 use serde_json::json;
 use utils::endpoints::{Endpoint, Method};
 use utils::prelude::*;
 
+static SCALE_POLL_INTERVAL_SECS: u64 = 3;
+
+// GPU models the cluster actually has nodes for; keep in sync with ops.
+static KNOWN_GPU_TYPES: [&str; 4] = ["a100", "a10g", "t4", "l4"];
+
 #[derive(Args, Clone)]
 pub struct ScaleServiceConf {
-    #[arg(help = "Name of the service")]
-    service_name: String,
+    #[arg(help = "Name of the service (defaults to the local mlx.toml when omitted)")]
+    service_name: Option<String>,
 
     #[arg(help = "Version of the service")]
-    service_version: String,
+    service_version: Option<String>,
 
     #[arg(long, help = "Replicas requested")]
     replicas: Option<u32>,
@@ -21,20 +27,80 @@ pub struct ScaleServiceConf {
     #[arg(long, help = "GPU cores or milicores requested")]
     gpu_limit: Option<String>,
 
+    #[arg(long, help = "GPU model to schedule onto (e.g. a100, t4)")]
+    gpu_type: Option<String>,
+
     #[arg(long, help = "Memory limit requested")]
     memory_limit: Option<String>,
 
     #[arg(long, help = "Number of concurrent jobs available per Service")]
     concurrent_jobs: Option<u32>,
+
+    #[arg(
+        long,
+        help = "Poll the service until the requested replica count is observed",
+        default_value = "false"
+    )]
+    wait: bool,
+
+    #[arg(
+        long,
+        help = "Seconds to poll for when --wait is set before giving up",
+        default_value_t = 120
+    )]
+    wait_timeout: u64,
 }
 
 #[tokio::main]
 pub async fn scale_service(conf: &ScaleServiceConf) -> RResult<(), AnyErr2> {
+    // A single positional reads as the version, with the service name
+    // defaulting from the local mlx.toml.
+    let (service_name, service_version) =
+        match (conf.service_name.clone(), conf.service_version.clone()) {
+            (Some(name), Some(version)) => (name, version),
+            (Some(only), None) => (resolve_service_name(None).await?, only),
+            _ => {
+                return Err(Report::new(err2!(
+                    "A service version is required: mlx serve scale [NAME] <VERSION>"
+                )))
+            }
+        };
+
+    if let Some(gpu_type) = &conf.gpu_type {
+        if !KNOWN_GPU_TYPES.contains(&gpu_type.to_lowercase().as_str()) {
+            return Err(Report::new(err2!(format!(
+                "Unknown GPU type '{}' - valid options: {}",
+                gpu_type,
+                KNOWN_GPU_TYPES.join(", ")
+            ))));
+        }
+    }
+
+    // Total capacity is replicas x concurrent_jobs; when both are set,
+    // surface the new capacity and warn if it drops below the recent job
+    // volume. Informational only - the scale still goes through.
+    if let (Some(replicas), Some(concurrent_jobs)) = (conf.replicas, conf.concurrent_jobs) {
+        let capacity = replicas * concurrent_jobs;
+        info!(
+            "New total capacity: {} concurrent jobs ({} replicas x {} jobs each)",
+            capacity, replicas, concurrent_jobs
+        );
+
+        if let Some(recent_jobs) = fetch_recent_job_count(&service_name).await {
+            if (recent_jobs as u32) > capacity {
+                warn!(
+                    "Capacity {} is below the recent job count {} - the service may queue under load",
+                    capacity, recent_jobs
+                );
+            }
+        }
+    }
+
     let mut endpoint_builder = Endpoint::builder()
-        .base_url(&get_server_url().await)
+        .base_url(&get_server_url().await?)
         .endpoint(&format!(
             "/scale_service/{}/{}",
-            conf.service_name, conf.service_version
+            service_name, service_version
         ))
         .method(Method::POST);
 
@@ -42,16 +108,98 @@ pub async fn scale_service(conf: &ScaleServiceConf) -> RResult<(), AnyErr2> {
         "replicas": conf.replicas,
         "cpu_limit": conf.cpu_limit,
         "gpu_limit": conf.gpu_limit,
+        "gpu_type": conf.gpu_type.as_ref().map(|t| t.to_lowercase()),
         "memory_limit": conf.memory_limit,
         "concurrent_jobs": conf.concurrent_jobs,
     });
-    let endpoint = endpoint_builder.json_body(body).build().unwrap();
+    let endpoint = endpoint_builder.json_body(body.clone()).build().unwrap();
     // let endpoint = endpoint_builder.build().unwrap();
 
-    endpoint
-        .send()
-        .await
-        .change_context(err2!("Failed delete_service request"))?;
+    send_endpoint(
+        endpoint,
+        "POST",
+        &format!("/scale_service/{}/{}", service_name, service_version),
+        Some(&body),
+        "Failed scale_service request",
+    )
+    .await?;
+
+    if conf.wait {
+        match conf.replicas {
+            Some(replicas) => {
+                wait_for_replicas(&service_name, &service_version, replicas, conf.wait_timeout)
+                    .await?
+            }
+            None => warn!("--wait has no effect without --replicas"),
+        }
+    }
 
     Ok(())
 }
+
+// Best-effort job volume from /jobs/{name}; None when the endpoint is
+// unavailable so the capacity hint simply stays silent.
+async fn fetch_recent_job_count(service_name: &str) -> Option<usize> {
+    let endpoint = Endpoint::builder()
+        .base_url(&get_server_url().await.ok()?)
+        .endpoint(&format!("/jobs/{}", service_name))
+        .method(Method::GET)
+        .build()
+        .unwrap();
+
+    let jobs = send_endpoint(
+        endpoint,
+        "GET",
+        &format!("/jobs/{}", service_name),
+        None,
+        "Failed to retrieve jobs",
+    )
+    .await
+    .ok()?;
+
+    jobs.as_object().map(|map| map.len())
+}
+
+async fn wait_for_replicas(
+    service_name: &str,
+    service_version: &str,
+    requested: u32,
+    wait_timeout: u64,
+) -> RResult<(), AnyErr2> {
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(wait_timeout);
+
+    while std::time::Instant::now() < deadline {
+        let response = fetch_services(Some(service_name), false).await?;
+        let services = response
+            .as_array()
+            .ok_or_else(|| err2!("Response is not an array"))?;
+
+        let observed = services
+            .iter()
+            .find(|service| service["version"].as_i64().unwrap_or(0).to_string() == service_version)
+            .and_then(|service| service["resource_request"]["replicas"].as_i64());
+
+        match observed {
+            Some(observed) if observed == i64::from(requested) => {
+                info!(
+                    "Service {} version {} scaled to {} replicas",
+                    service_name, service_version, requested
+                );
+                return Ok(());
+            }
+            Some(observed) => {
+                info!("Scaling in progress: {}/{} replicas", observed, requested);
+            }
+            None => {
+                info!("Waiting for service {} to report replicas", service_name);
+            }
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(SCALE_POLL_INTERVAL_SECS)).await;
+    }
+
+    Err(Report::new(err2!(format!(
+        "Timed out after {}s waiting for {} to reach {} replicas",
+        wait_timeout, service_name, requested
+    ))))
+}
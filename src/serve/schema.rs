@@ -0,0 +1,71 @@
+use crate::serve::list::fetch_services;
+use crate::serve::{confirm_overwrite, resolve_service_name, ServiceParams};
+use utils::prelude::*;
+
+// Fetches the deployed schema for a service and writes it to the local
+// schema.json (or stdout), so a drifted local copy can be re-synced with
+// what is actually running.
+#[tokio::main]
+pub async fn pull_schema_service(
+    service_name: Option<String>,
+    version: Option<u32>,
+    stdout: bool,
+    force: bool,
+) -> RResult<(), AnyErr2> {
+    let service_name = resolve_service_name(service_name).await?;
+
+    let response = fetch_services(Some(&service_name), false).await?;
+    let services = response
+        .as_array()
+        .ok_or_else(|| err2!("Response is not an array"))?;
+
+    // Latest version wins unless one was asked for explicitly.
+    let service = match version {
+        Some(version) => services
+            .iter()
+            .find(|s| s["version"].as_i64() == Some(version as i64))
+            .ok_or_else(|| {
+                Report::new(err2!(format!(
+                    "Service {} has no version {}",
+                    service_name, version
+                )))
+            })?,
+        None => services
+            .iter()
+            .max_by_key(|s| s["version"].as_i64().unwrap_or(0))
+            .ok_or_else(|| Report::new(err2!(format!("Service {} not found", service_name))))?,
+    };
+
+    let schema = service
+        .get("service_schema")
+        .ok_or_else(|| err2!("Service record has no service_schema field"))?;
+
+    // Round-trip through the deploy-side types so what we write is exactly
+    // what a deploy would accept back.
+    let params = ServiceParams::from_value(schema)?;
+    let pretty =
+        serde_json::to_string_pretty(&params).change_context(err2!("Failed to render schema"))?;
+
+    if stdout {
+        println!("{}", pretty);
+        return Ok(());
+    }
+
+    let target = crate::SERVICE_CONFIG_PATH;
+    if std::path::Path::new(target).exists() && !force && !confirm_overwrite(target) {
+        info!("Aborted - {} left untouched", target);
+        return Ok(());
+    }
+
+    std::fs::write(target, pretty + "\n")
+        .change_context(err2!(format!("Failed to write {}", target)))?;
+
+    info!(
+        "Wrote deployed schema of {} (version {}) to {}",
+        service_name,
+        service["version"].as_i64().unwrap_or(0),
+        target
+    );
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use crate::serve::list::fetch_services;
+use crate::serve::{clear_screen, get_server_url, resolve_service_name, send_endpoint};
+use chrono::Utc;
+use comfy_table::modifiers::UTF8_ROUND_CORNERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Cell, CellAlignment, ContentArrangement, Table};
+use utils::endpoints::{Endpoint, Method};
+use utils::prelude::*;
+
+#[tokio::main]
+pub async fn status_service(
+    service_name: Option<String>,
+    watch: bool,
+    interval: u64,
+) -> RResult<(), AnyErr2> {
+    let service_name = resolve_service_name(service_name).await?;
+
+    if !watch {
+        return render_status(&service_name).await;
+    }
+
+    loop {
+        clear_screen();
+
+        // Transient fetch errors shouldn't kill the watch loop.
+        if let Err(report) = render_status(&service_name).await {
+            warn!("Failed to refresh status: {:?}", report);
+        }
+
+        println!("Last refresh: {} (Ctrl-C to exit)", Utc::now().to_rfc3339());
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+    }
+}
+
+async fn render_status(service_name: &str) -> RResult<(), AnyErr2> {
+    let response = fetch_services(Some(service_name), false).await?;
+    let services = response
+        .as_array()
+        .ok_or_else(|| err2!("Response is not an array"))?;
+
+    if services.is_empty() {
+        info!("No services found for '{}'", service_name);
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_width(180)
+        .set_header(vec![
+            "Version",
+            "Running",
+            "Replicas",
+            "CPU Limit",
+            "Memory Limit",
+            "Pod ID",
+        ]);
+
+    for service in services {
+        let version = service["version"].as_i64().unwrap_or(0).to_string();
+        let running = service["running"].as_bool().unwrap_or(false).to_string();
+        let replicas = service["resource_request"]["replicas"]
+            .as_i64()
+            .unwrap_or(0)
+            .to_string();
+        let cpu_limit = service["resource_request"]["cpu_limit"]
+            .as_str()
+            .unwrap_or("-");
+        let memory_limit = service["resource_request"]["memory_limit"]
+            .as_str()
+            .unwrap_or("-");
+        let pod_id = service["pod_id"].as_str().unwrap_or("-");
+
+        table.add_row(vec![
+            Cell::new(version).set_alignment(CellAlignment::Center),
+            Cell::new(running).set_alignment(CellAlignment::Center),
+            Cell::new(replicas).set_alignment(CellAlignment::Center),
+            Cell::new(cpu_limit),
+            Cell::new(memory_limit),
+            Cell::new(pod_id),
+        ]);
+    }
+
+    println!("Service: {}", service_name);
+    println!("{table}");
+
+    // Latest job is best-effort extra context; the status table is still
+    // useful when the jobs endpoint is unavailable.
+    let endpoint = Endpoint::builder()
+        .base_url(&get_server_url().await?)
+        .endpoint(&format!("/jobs/{}", service_name))
+        .method(Method::GET)
+        .build()
+        .unwrap();
+
+    if let Ok(jobs) = send_endpoint(
+        endpoint,
+        "GET",
+        &format!("/jobs/{}", service_name),
+        None,
+        "Failed to retrieve jobs",
+    )
+    .await
+    {
+        if let Some(jobs) = jobs.as_object() {
+            let latest = jobs
+                .iter()
+                .max_by_key(|(_, log)| log["started_at"].as_str().unwrap_or("").to_string());
+
+            if let Some((job_id, log)) = latest {
+                println!(
+                    "Latest job: {} (started {})",
+                    job_id,
+                    log["started_at"].as_str().unwrap_or("-")
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use crate::serve::list::fetch_services;
+use crate::serve::{clear_screen, get_server_url, send_endpoint};
+use chrono::Utc;
+use comfy_table::modifiers::UTF8_ROUND_CORNERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Cell, CellAlignment, ContentArrangement, Table};
+use std::collections::HashMap;
+use utils::endpoints::{Endpoint, Method};
+use utils::prelude::*;
+
+// Resource the top view sorts (descending) by.
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum TopSort {
+    Cpu,
+    Memory,
+    Jobs,
+}
+
+// Per-service aggregate across all deployed versions.
+struct ServiceUsage {
+    name: String,
+    cpu: f64,
+    memory_mib: f64,
+    replicas: i64,
+    jobs: usize,
+}
+
+// htop-style overview of the heaviest services by requested CPU, memory
+// and job volume, optionally refreshing like the other --watch loops.
+#[tokio::main]
+pub async fn top_services(sort: TopSort, watch: bool, interval: u64) -> RResult<(), AnyErr2> {
+    if !watch {
+        return render_top(sort).await;
+    }
+
+    loop {
+        clear_screen();
+
+        // Transient fetch errors shouldn't kill the watch loop.
+        if let Err(report) = render_top(sort).await {
+            warn!("Failed to refresh top view: {:?}", report);
+        }
+
+        println!("Last refresh: {} (Ctrl-C to exit)", Utc::now().to_rfc3339());
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(interval)).await;
+    }
+}
+
+async fn render_top(sort: TopSort) -> RResult<(), AnyErr2> {
+    let response = fetch_services(None, false).await?;
+    let services = response
+        .as_array()
+        .ok_or_else(|| err2!("Response is not an array"))?;
+
+    // Sum requested resources per service name, weighting limits by the
+    // replica count of each version.
+    let mut usage: HashMap<String, ServiceUsage> = HashMap::new();
+    for service in services {
+        let name = service["name"].as_str().unwrap_or("-").to_string();
+        let replicas = service["resource_request"]["replicas"]
+            .as_i64()
+            .unwrap_or(0);
+        let cpu = parse_cpu(&service["resource_request"]["cpu_limit"]);
+        let memory = parse_memory_mib(&service["resource_request"]["memory_limit"]);
+
+        let entry = usage.entry(name.clone()).or_insert(ServiceUsage {
+            name,
+            cpu: 0.0,
+            memory_mib: 0.0,
+            replicas: 0,
+            jobs: 0,
+        });
+        entry.cpu += cpu * replicas.max(1) as f64;
+        entry.memory_mib += memory * replicas.max(1) as f64;
+        entry.replicas += replicas;
+    }
+
+    // Job volume is best-effort extra signal; a missing jobs endpoint
+    // still leaves a useful resource table.
+    for entry in usage.values_mut() {
+        entry.jobs = fetch_job_count(&entry.name).await.unwrap_or(0);
+    }
+
+    let mut rows: Vec<ServiceUsage> = usage.into_values().collect();
+    match sort {
+        TopSort::Cpu => rows.sort_by(|a, b| b.cpu.total_cmp(&a.cpu)),
+        TopSort::Memory => rows.sort_by(|a, b| b.memory_mib.total_cmp(&a.memory_mib)),
+        TopSort::Jobs => rows.sort_by(|a, b| b.jobs.cmp(&a.jobs)),
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_width(180)
+        .set_header(vec![
+            "Name",
+            "CPU (cores)",
+            "Memory (Mi)",
+            "Replicas",
+            "Jobs",
+        ]);
+
+    for row in rows {
+        table.add_row(vec![
+            Cell::new(row.name),
+            Cell::new(format!("{:.1}", row.cpu)).set_alignment(CellAlignment::Right),
+            Cell::new(format!("{:.0}", row.memory_mib)).set_alignment(CellAlignment::Right),
+            Cell::new(row.replicas).set_alignment(CellAlignment::Center),
+            Cell::new(row.jobs).set_alignment(CellAlignment::Center),
+        ]);
+    }
+
+    println!("{table}");
+
+    Ok(())
+}
+
+async fn fetch_job_count(service_name: &str) -> Option<usize> {
+    let endpoint = Endpoint::builder()
+        .base_url(&get_server_url().await.ok()?)
+        .endpoint(&format!("/jobs/{}", service_name))
+        .method(Method::GET)
+        .build()
+        .unwrap();
+
+    let jobs = send_endpoint(
+        endpoint,
+        "GET",
+        &format!("/jobs/{}", service_name),
+        None,
+        "Failed to retrieve jobs",
+    )
+    .await
+    .ok()?;
+
+    jobs.as_object().map(|map| map.len())
+}
+
+fn parse_cpu(value: &serde_json::Value) -> f64 {
+    match value {
+        serde_json::Value::Number(n) => n.as_f64().unwrap_or(0.0),
+        serde_json::Value::String(s) => s.parse().unwrap_or(0.0),
+        _ => 0.0,
+    }
+}
+
+// Accepts the quantity strings the server returns ("512Mi", "2Gi") as
+// well as bare numbers, normalized to MiB.
+fn parse_memory_mib(value: &serde_json::Value) -> f64 {
+    let raw = match value {
+        serde_json::Value::Number(n) => return n.as_f64().unwrap_or(0.0),
+        serde_json::Value::String(s) => s,
+        _ => return 0.0,
+    };
+
+    if let Some(gib) = raw.strip_suffix("Gi") {
+        return gib.parse::<f64>().unwrap_or(0.0) * 1024.0;
+    }
+    if let Some(mib) = raw.strip_suffix("Mi") {
+        return mib.parse().unwrap_or(0.0);
+    }
+
+    raw.parse().unwrap_or(0.0)
+}
@@ -0,0 +1,87 @@
+use crate::serve::create::{ServiceParams, TomlConfig};
+use crate::serve::run::{validate_tests, TestConfig};
+use crate::{SERVICE_CONFIG_PATH, SERVICE_TOML_PATH};
+use utils::cmd::run_python_script;
+use utils::prelude::*;
+
+// Fast inner-loop correctness check: regenerates schema.json, parses it
+// together with mlx.toml and cross-validates the test specs - all locally,
+// no image build and no network. Reports every problem in one pass and
+// exits non-zero on any failure.
+pub fn validate_service() {
+    let mut ok = true;
+
+    info!("Generating schema.json from main.py...");
+    run_python_script("main.py", Some(&["--build", "1"]));
+
+    let service_params = match std::fs::read_to_string(SERVICE_CONFIG_PATH) {
+        Ok(contents) => match ServiceParams::from_json(&contents) {
+            Ok(params) => {
+                info!("schema.json: OK");
+                Some(params)
+            }
+            Err(report) => {
+                error!("schema.json: {:?}", report);
+                ok = false;
+                None
+            }
+        },
+        Err(e) => {
+            error!("schema.json: {}", e);
+            ok = false;
+            None
+        }
+    };
+
+    let toml_content = match std::fs::read_to_string(SERVICE_TOML_PATH) {
+        Ok(contents) => contents,
+        Err(e) => {
+            error!("mlx.toml: {}", e);
+            summarize(false);
+            return;
+        }
+    };
+
+    match toml::from_str::<TomlConfig>(&toml_content) {
+        Ok(_) => info!("mlx.toml service/resources: OK"),
+        Err(e) => {
+            error!("mlx.toml service/resources: {}", e);
+            ok = false;
+        }
+    }
+
+    let test_config = match toml::from_str::<TestConfig>(&toml_content) {
+        Ok(config) => {
+            info!("mlx.toml test specs: OK");
+            Some(config)
+        }
+        Err(e) => {
+            error!("mlx.toml test specs: {}", e);
+            ok = false;
+            None
+        }
+    };
+
+    if let (Some(service_params), Some(test_config)) = (service_params, test_config) {
+        let tests = test_config.test.keys().cloned().collect::<Vec<String>>();
+
+        match validate_tests(tests, &test_config, &service_params) {
+            Ok(_) => info!("Test specs vs schema: OK"),
+            Err(report) => {
+                error!("Test specs vs schema: {:?}", report);
+                ok = false;
+            }
+        }
+    }
+
+    summarize(ok);
+}
+
+fn summarize(ok: bool) {
+    if ok {
+        info!("Validation passed");
+    } else {
+        error!("Validation failed");
+        std::process::exit(1);
+    }
+}
@@ -1,17 +1,87 @@
 use anyhow::Result;
-use redis::AsyncCommands;
+use chrono::Utc;
+use comfy_table::modifiers::UTF8_ROUND_CORNERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Cell, CellAlignment, ContentArrangement, Table};
 use regex::Regex;
 use tracing::{error, info};
+use utils::endpoints::{Endpoint, Method};
+use utils::prelude::*;
 
 // mod utils;
+use crate::serve::{get_server_url, send_endpoint};
 use redis::Commands;
 use utils::redis_manager::RedisManager;
 
+// Lists the experiments run remotely, mirroring how list_services renders
+// deployed services.
+#[tokio::main]
+pub async fn list_experiments() -> RResult<(), AnyErr2> {
+    let endpoint = Endpoint::builder()
+        .base_url(&get_server_url().await?)
+        .endpoint("/list_experiments")
+        .method(Method::GET)
+        .build()
+        .unwrap();
+
+    let response = send_endpoint(
+        endpoint,
+        "GET",
+        "/list_experiments",
+        None,
+        "Failed list_experiments request",
+    )
+    .await?;
+
+    let experiments = response
+        .as_array()
+        .ok_or_else(|| err2!("Response is not an array"))?;
+
+    if experiments.is_empty() {
+        info!("No experiments found");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .apply_modifier(UTF8_ROUND_CORNERS)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_width(180)
+        .set_header(vec!["Name", "Run", "Status", "Started At"]);
+
+    for experiment in experiments {
+        table.add_row(vec![
+            Cell::new(experiment["name"].as_str().unwrap_or("-")),
+            Cell::new(experiment["run"].as_str().unwrap_or("-"))
+                .set_alignment(CellAlignment::Center),
+            Cell::new(experiment["status"].as_str().unwrap_or("-"))
+                .set_alignment(CellAlignment::Center),
+            Cell::new(experiment["started_at"].as_str().unwrap_or("-"))
+                .set_alignment(CellAlignment::Center),
+        ]);
+    }
+
+    println!("{table}");
+
+    Ok(())
+}
+
+// Output format for streamed experiment logs, selectable via --format on
+// `mlx xp logs`.
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 struct TrainingMetrics {
     training_iteration: Option<usize>,
     batch: Option<usize>,
     epoch: Option<usize>,
+    loss: Option<f64>,
+    accuracy: Option<f64>,
 }
 
 impl TrainingMetrics {
@@ -20,14 +90,24 @@ impl TrainingMetrics {
             training_iteration: None,
             batch: None,
             epoch: None,
+            loss: None,
+            accuracy: None,
         }
     }
 }
 
 fn _parse_training_output(line: &str, metrics: &mut TrainingMetrics) {
-    let re_iteration = Regex::new(r"training_iteration\s+(\d+)").unwrap();
-    let re_batch = Regex::new(r"batch\s+(\d+)").unwrap();
-    let re_epoch = Regex::new(r"epoch\s+(\d+)").unwrap();
+    // Each metric name is anchored to a word boundary on the left so
+    // derived names like "val_loss" don't overwrite the training metric.
+    let re_iteration = Regex::new(r"(?:^|[^0-9A-Za-z_])training_iteration\s+(\d+)").unwrap();
+    let re_batch = Regex::new(r"(?:^|[^0-9A-Za-z_])batch\s+(\d+)").unwrap();
+    let re_epoch = Regex::new(r"(?:^|[^0-9A-Za-z_])epoch\s+(\d+)").unwrap();
+    // Floats with optional scientific notation, e.g. "loss: 1.2e-4".
+    let re_loss =
+        Regex::new(r"(?:^|[^0-9A-Za-z_])loss[\s=:]+([0-9]*\.?[0-9]+(?:[eE][+-]?[0-9]+)?)").unwrap();
+    let re_accuracy =
+        Regex::new(r"(?:^|[^0-9A-Za-z_])accuracy[\s=:]+([0-9]*\.?[0-9]+(?:[eE][+-]?[0-9]+)?)")
+            .unwrap();
 
     if let Some(caps) = re_iteration.captures(line) {
         metrics.training_iteration = Some(caps[1].parse().unwrap_or(0));
@@ -38,65 +118,305 @@ fn _parse_training_output(line: &str, metrics: &mut TrainingMetrics) {
     if let Some(caps) = re_epoch.captures(line) {
         metrics.epoch = Some(caps[1].parse().unwrap_or(0));
     }
+    if let Some(value) = re_loss.captures(line).and_then(|caps| caps[1].parse().ok()) {
+        metrics.loss = Some(value);
+    }
+    if let Some(value) = re_accuracy
+        .captures(line)
+        .and_then(|caps| caps[1].parse().ok())
+    {
+        metrics.accuracy = Some(value);
+    }
 }
 
-fn customize_data_loader(metrics: &TrainingMetrics) {
-    error!(
-        "Customizing data loader for epoch {:?}, batch {:?}, training iteration {:?}",
-        metrics.epoch, metrics.batch, metrics.training_iteration
+// One-line live status redrawn in place with a carriage return, so the
+// stream shows training progress instead of a wall of repeated metric
+// lines. Only called when a metric actually changed.
+fn render_metrics_line(metrics: &TrainingMetrics) {
+    let fmt = |value: &Option<usize>| {
+        value
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    let mut status = format!(
+        "epoch {} | batch {} | iteration {}",
+        fmt(&metrics.epoch),
+        fmt(&metrics.batch),
+        fmt(&metrics.training_iteration)
     );
+    if let Some(loss) = metrics.loss {
+        status.push_str(&format!(" | loss {}", loss));
+    }
+    if let Some(accuracy) = metrics.accuracy {
+        status.push_str(&format!(" | accuracy {}", accuracy));
+    }
+
+    print!("\r{}    ", status);
+    let _ = std::io::Write::flush(&mut std::io::stdout());
+}
+
+// Prints one streamed line in the chosen format. The json format wraps
+// each line (plus any parsed metrics) as a single JSON object so the
+// stream can be piped into a log aggregator.
+fn emit_log_line(line: &str, metrics: &TrainingMetrics, format: LogFormat) {
+    match format {
+        LogFormat::Text => println!("{}", line),
+        LogFormat::Json => {
+            let record = serde_json::json!({
+                "timestamp": Utc::now().to_rfc3339(),
+                "line": line,
+                "training_iteration": metrics.training_iteration,
+                "batch": metrics.batch,
+                "epoch": metrics.epoch,
+                "loss": metrics.loss,
+                "accuracy": metrics.accuracy,
+            });
+            println!("{}", record);
+        }
+    }
+}
+
+// Seconds blpop waits for a new line before giving up, so the stream
+// terminates even when an experiment never emits its done marker. Long
+// training phases can go quiet for many minutes, so the default is
+// generous and MLX_XP_IDLE_TIMEOUT_SECS overrides it for impatient CI.
+static IDLE_TIMEOUT_ENV: &str = "MLX_XP_IDLE_TIMEOUT_SECS";
+static DEFAULT_IDLE_TIMEOUT_SECS: u64 = 1800;
 
-    // Implement your data loader customization logic here
+fn idle_timeout_secs() -> u64 {
+    std::env::var(IDLE_TIMEOUT_ENV)
+        .ok()
+        .and_then(|raw| raw.parse().ok())
+        .filter(|&secs| secs >= 1)
+        .unwrap_or(DEFAULT_IDLE_TIMEOUT_SECS)
 }
 
-pub async fn stream_logs() -> Result<()> {
-    let connection_string = "redis://:MkiTVpOWFVLGLgJ7ptZ29dY80zER4cvR@redis-17902.c322.us-east-1-2.ec2.redns.redis-cloud.com:17902";
+pub async fn stream_logs(name: &str, run: &str, format: LogFormat) -> Result<()> {
+    let connection_string =
+        crate::config::redis_url().map_err(|report| anyhow::anyhow!("{:?}", report))?;
 
-    let mut redis = RedisManager::new(connection_string)?;
+    let mut redis = RedisManager::new(&connection_string)?;
 
-    let queue_name = "my_experiment_stdout";
+    // One stdout queue per experiment run.
+    let queue_name = format!("{}:{}:stdout", name, run);
 
     info!("Reading from Redis queue: {}", queue_name);
 
+    let idle_timeout_secs = idle_timeout_secs();
     let mut metrics = TrainingMetrics::new();
-    // let mut buffer: Vec<TrainingMetrics> = Vec::new();
-
-    // loop {
-    //     match redis
-    //         .client
-    //         .blpop::<&str, (String, String)>(queue_name, 0.0)?
-    //     {
-    //         Ok(log_entry) => {
-    //             let line = log_entry.1.clone();
-
-    //             // info!("Log: {}", log_entry.1);
-
-    //             if log_entry.1.contains("is_done") {
-    //                 info!("Experiment completed, exiting...");
-    //                 break;
-    //             }
-
-    //             if line.contains("training_iteration")
-    //                 || line.contains("batch")
-    //                 || line.contains("epoch")
-    //             {
-    //                 let old_metrics = metrics.clone();
-
-    //                 _parse_training_output(&line, &mut metrics);
-
-    //                 if metrics != old_metrics {
-    //                     // buffer.push(metrics.clone());
-    //                     customize_data_loader(&metrics);
-    //                 }
-    //             }
-    //         }
-    //         Err(e) => {
-    //             error!("Error fetching logs from Redis: {:?}", e);
-    //             break;
-    //             // tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-    //         }
-    //     }
-    // }
+    // Whether the in-place metrics line is currently on screen, so plain
+    // lines don't print over it.
+    let mut status_active = false;
+
+    loop {
+        // A None reply means blpop timed out with no new output.
+        match redis
+            .client
+            .blpop::<&str, Option<(String, String)>>(&queue_name, idle_timeout_secs as f64)
+        {
+            Ok(Some(log_entry)) => {
+                let line = log_entry.1;
+
+                if line.contains("is_done") {
+                    if status_active {
+                        println!();
+                    }
+                    info!("Experiment completed, exiting...");
+                    break;
+                }
+
+                let is_metric_line = line.contains("training_iteration")
+                    || line.contains("batch")
+                    || line.contains("epoch")
+                    || line.contains("loss")
+                    || line.contains("accuracy");
+
+                if is_metric_line {
+                    let old_metrics = metrics.clone();
+
+                    _parse_training_output(&line, &mut metrics);
+
+                    // Metric lines feed the live status line in text mode
+                    // instead of scrolling past; json consumers still get
+                    // one record per line.
+                    match format {
+                        LogFormat::Text => {
+                            if metrics != old_metrics {
+                                render_metrics_line(&metrics);
+                                status_active = true;
+                            } else {
+                                // Mentions a metric but didn't move one
+                                // (e.g. "early stopping: loss did not
+                                // improve") - print it like any other
+                                // line instead of dropping it.
+                                if status_active {
+                                    println!();
+                                    status_active = false;
+                                }
+                                emit_log_line(&line, &metrics, format);
+                            }
+                        }
+                        LogFormat::Json => emit_log_line(&line, &metrics, format),
+                    }
+                } else {
+                    if status_active {
+                        println!();
+                        status_active = false;
+                    }
+                    emit_log_line(&line, &metrics, format);
+                }
+            }
+            Ok(None) => {
+                if status_active {
+                    println!();
+                }
+                info!(
+                    "No output received for {}s - stopping the stream (tune with {})",
+                    idle_timeout_secs, IDLE_TIMEOUT_ENV
+                );
+                break;
+            }
+            Err(e) => {
+                if status_active {
+                    println!();
+                }
+                error!("Error fetching logs from Redis: {:?}", e);
+                return Err(e.into());
+            }
+        }
+    }
 
     Ok(())
 }
+
+// How long `xp board` waits for the server to spin a TensorBoard up.
+static BOARD_POLL_INTERVAL_SECS: u64 = 2;
+static BOARD_POLL_ATTEMPTS: u32 = 15;
+
+// Requests a TensorBoard session for the run and opens the returned URL
+// once the server reports it ready. Boards take a few seconds to spin up,
+// so a pending response is polled rather than treated as a failure.
+#[tokio::main]
+pub async fn launch_board(name: &str, run: &str) -> RResult<(), AnyErr2> {
+    let path = format!("/board/{}/{}", name, run);
+
+    for attempt in 0..BOARD_POLL_ATTEMPTS {
+        let endpoint = Endpoint::builder()
+            .base_url(&get_server_url().await?)
+            .endpoint(&path)
+            .method(Method::GET)
+            .build()
+            .unwrap();
+
+        let response = match send_endpoint(endpoint, "GET", &path, None, "Failed board request")
+            .await
+        {
+            Ok(response) => response,
+            Err(report) => {
+                // A 404 means no board exists for this run - not a failure
+                // worth a stack trace.
+                if crate::serve::report_status(&report) == Some(reqwest::StatusCode::NOT_FOUND) {
+                    info!(
+                        "No TensorBoard available for experiment '{}' run '{}' - has the run logged any summaries?",
+                        name, run
+                    );
+                    return Ok(());
+                }
+                return Err(report);
+            }
+        };
+
+        // Ready responses carry a url (or a port to forward locally);
+        // anything else means the board is still spinning up.
+        let url = response
+            .get("url")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| {
+                response
+                    .get("port")
+                    .and_then(|v| v.as_u64())
+                    .map(|port| format!("http://localhost:{}", port))
+            });
+
+        if let Some(url) = url {
+            info!("TensorBoard for {} run {} is at {}", name, run, url);
+            open_in_browser(&url);
+            return Ok(());
+        }
+
+        if attempt + 1 < BOARD_POLL_ATTEMPTS {
+            info!(
+                "Board not ready yet - retrying in {}s",
+                BOARD_POLL_INTERVAL_SECS
+            );
+            tokio::time::sleep(tokio::time::Duration::from_secs(BOARD_POLL_INTERVAL_SECS)).await;
+        }
+    }
+
+    Err(Report::new(err2!(format!(
+        "TensorBoard for {} run {} did not become ready in time",
+        name, run
+    ))))
+}
+
+// Default Ray dashboard port; `ray start` binds it unless overridden.
+static RAY_DASHBOARD_PORT: u16 = 8265;
+
+// Resolves the Ray dashboard URL and opens it. "auto" assumes a local
+// Ray, whose dashboard binds to localhost; an explicit host[:port]
+// address swaps the client port for the dashboard's.
+#[tokio::main]
+pub async fn open_ray_dashboard(ray_address: &str) -> RResult<(), AnyErr2> {
+    let host = if ray_address == "auto" {
+        "127.0.0.1".to_string()
+    } else {
+        let trimmed = ray_address
+            .trim_start_matches("ray://")
+            .trim_start_matches("http://")
+            .trim_start_matches("https://");
+        trimmed
+            .split(':')
+            .next()
+            .filter(|host| !host.is_empty())
+            .unwrap_or("127.0.0.1")
+            .to_string()
+    };
+
+    let url = format!("http://{}:{}", host, RAY_DASHBOARD_PORT);
+
+    // Short probe so a missing Ray fails with a clear message instead of
+    // a browser tab pointing at nothing.
+    let client = reqwest::Client::builder()
+        .connect_timeout(std::time::Duration::from_secs(2))
+        .timeout(std::time::Duration::from_secs(2))
+        .build()
+        .change_context(err2!("Failed to build the probe client"))?;
+
+    match client.get(&url).send().await {
+        Ok(res) if res.status().is_success() || res.status().is_redirection() => {
+            info!("Ray dashboard is at {}", url);
+            open_in_browser(&url);
+            Ok(())
+        }
+        _ => Err(Report::new(err2!(format!(
+            "Ray dashboard not reachable at {} - is Ray running? Start one with `ray start --head`",
+            url
+        )))),
+    }
+}
+
+// Best-effort browser launch; the URL is already printed so a failure
+// here costs nothing.
+fn open_in_browser(url: &str) {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+
+    if let Err(e) = std::process::Command::new(opener).arg(url).spawn() {
+        warn!("Failed to open a browser ({}) - open {} manually", e, url);
+    }
+}